@@ -1,4 +1,8 @@
+use crate::template::{self, Field, ListTemplate};
+use chrono::format::{Item, StrftimeItems};
+use ratatui::style::{Modifier, Style};
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct FeedConfigEntry {
@@ -7,6 +11,12 @@ pub struct FeedConfigEntry {
     #[serde(default)]
     pub enabled: bool,
     pub filter: Option<String>,
+    // Feeds sharing a group are shown together under the same tab
+    pub group: Option<String>,
+    // Overrides the global sorting for this feed
+    pub sort: Option<SortConfiguration>,
+    // Overrides the global renderer for this feed
+    pub renderer: Option<RendererConfiguration>,
 }
 
 impl Default for FeedConfigEntry {
@@ -16,10 +26,51 @@ impl Default for FeedConfigEntry {
             url: String::new(),
             enabled: true,
             filter: None,
+            group: None,
+            sort: None,
+            renderer: None,
         }
     }
 }
 
+#[derive(Debug, Default, Deserialize, Clone, Copy)]
+pub enum SortKey {
+    #[default]
+    #[serde(alias = "original", alias = "default")]
+    Original,
+    #[serde(alias = "published")]
+    Published,
+    #[serde(alias = "updated")]
+    Updated,
+    #[serde(alias = "title")]
+    Title,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    #[serde(alias = "ascending", alias = "asc")]
+    Ascending,
+    #[serde(alias = "descending", alias = "desc")]
+    Descending,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct SortConfiguration {
+    #[serde(default)]
+    pub by: SortKey,
+    pub order: Option<SortOrder>,
+}
+
+impl SortConfiguration {
+    // Dates default to newest first, everything else keeps its natural order
+    pub fn order(&self) -> SortOrder {
+        self.order.unwrap_or(match self.by {
+            SortKey::Original | SortKey::Title => SortOrder::Ascending,
+            SortKey::Published | SortKey::Updated => SortOrder::Descending,
+        })
+    }
+}
+
 // Purely just a workaround since it is very annoying to parse stuff from #(whatever)
 // TODO: Parse string properly without it being like this
 #[derive(Debug, Default, Deserialize, Clone)]
@@ -49,11 +100,258 @@ pub enum ListFormat {
     Extended,
 }
 
+impl ListFormat {
+    pub fn template(&self) -> ListTemplate {
+        let template = match self {
+            Self::Compact => template::COMPACT,
+            Self::Extended => template::EXTENDED,
+        };
+        ListTemplate::try_from(template.to_string()).expect("Built-in list templates are valid")
+    }
+}
+
+// Styling of a single field in list templates
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct FieldStyleConfiguration {
+    pub color: Option<ColorConfiguration>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub dim: bool,
+    #[serde(default)]
+    pub underlined: bool,
+}
+
+impl From<&FieldStyleConfiguration> for Style {
+    fn from(value: &FieldStyleConfiguration) -> Self {
+        let mut style = Self::new();
+        if let Some(color) = &value.color {
+            style = style.fg(ratatui::style::Color::Rgb(
+                color.red,
+                color.green,
+                color.blue,
+            ));
+        }
+        for (enabled, modifier) in [
+            (value.bold, Modifier::BOLD),
+            (value.italic, Modifier::ITALIC),
+            (value.dim, Modifier::DIM),
+            (value.underlined, Modifier::UNDERLINED),
+        ] {
+            if enabled {
+                style = style.add_modifier(modifier);
+            }
+        }
+        style
+    }
+}
+
+// How entry dates are shown in lists, always in the local time zone
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum DateFormat {
+    // 2024-11-09
+    #[default]
+    Iso,
+    // 3 days ago
+    Relative,
+    // Any strftime pattern, like "%d/%m %H:%M"
+    Custom(String),
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "iso" | "default" => Ok(Self::Iso),
+            "relative" => Ok(Self::Relative),
+            pattern if StrftimeItems::new(pattern).any(|item| item == Item::Error) => {
+                Err(format!("invalid date format \"{pattern}\""))
+            }
+            _ => Ok(Self::Custom(value)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum IconPreset {
+    #[serde(alias = "nerd-font", alias = "nerd_font", alias = "nerd")]
+    NerdFont,
+    // Single-width symbols every font has
+    #[serde(alias = "unicode")]
+    Unicode,
+    #[serde(alias = "emoji")]
+    Emoji,
+    #[serde(alias = "ascii")]
+    Ascii,
+}
+
+// Glyphs picked from the preset, set ones replace it
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct IconsConfiguration {
+    // Defaults to "nerd-font" when nerd_fonts is turned on, "unicode" otherwise
+    pub preset: Option<IconPreset>,
+    pub date: Option<String>,
+    pub title: Option<String>,
+    pub new: Option<String>,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+    pub back: Option<String>,
+    pub quit: Option<String>,
+    pub up: Option<String>,
+    pub down: Option<String>,
+    pub highlight: Option<String>,
+    pub error: Option<String>,
+    pub separator: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Icons {
+    pub date: String,
+    pub title: String,
+    // Badge of entries that are new since the last run
+    pub new: String,
+    // Also used for scrolling left and right
+    pub prev: String,
+    pub next: String,
+    pub back: String,
+    pub quit: String,
+    pub up: String,
+    pub down: String,
+    // In front of the highlighted item of lists
+    pub highlight: String,
+    // Feeds that failed to load
+    pub error: String,
+    // Between the parts of the status line and of titles
+    pub separator: String,
+}
+
+impl Icons {
+    pub fn preset(preset: IconPreset) -> Self {
+        let [date, title, new, prev, next, back, quit, up, down, highlight, error, separator] =
+            match preset {
+                IconPreset::NerdFont => {
+                    ["󰃭", "󰦨", "󰎔", "", "", "", "", "", "", "❯", "", "·"]
+                }
+                IconPreset::Unicode => ["◷", "¶", "✦", "←", "→", "←", "×", "↑", "↓", "❯", "✗", "·"],
+                IconPreset::Emoji => [
+                    "📅", "📜", "🆕", "⏪", "⏩", "🔙", "❌", "🔼", "🔽", "👉", "❗", "·",
+                ],
+                IconPreset::Ascii => ["@", "-", "+", "<", ">", "<", "x", "^", "v", ">", "!", "|"],
+            };
+
+        Self {
+            date: date.to_string(),
+            title: title.to_string(),
+            new: new.to_string(),
+            prev: prev.to_string(),
+            next: next.to_string(),
+            back: back.to_string(),
+            quit: quit.to_string(),
+            up: up.to_string(),
+            down: down.to_string(),
+            highlight: highlight.to_string(),
+            error: error.to_string(),
+            separator: separator.to_string(),
+        }
+    }
+}
+
+impl IconsConfiguration {
+    pub fn icons(&self, nerd_fonts: bool) -> Icons {
+        let preset = self.preset.unwrap_or(if nerd_fonts {
+            IconPreset::NerdFont
+        } else {
+            IconPreset::Unicode
+        });
+        let icons = Icons::preset(preset);
+        let pick = |glyph: &Option<String>, fallback: String| glyph.clone().unwrap_or(fallback);
+
+        Icons {
+            date: pick(&self.date, icons.date),
+            title: pick(&self.title, icons.title),
+            new: pick(&self.new, icons.new),
+            prev: pick(&self.prev, icons.prev),
+            next: pick(&self.next, icons.next),
+            back: pick(&self.back, icons.back),
+            quit: pick(&self.quit, icons.quit),
+            up: pick(&self.up, icons.up),
+            down: pick(&self.down, icons.down),
+            highlight: pick(&self.highlight, icons.highlight),
+            error: pick(&self.error, icons.error),
+            separator: pick(&self.separator, icons.separator),
+        }
+    }
+}
+
+// What the external renderer receives on its stdin
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RendererInput {
+    // The article as the built-in renderer shows it, minus the styling
+    #[default]
+    #[serde(alias = "text", alias = "plain")]
+    Text,
+    #[serde(alias = "html")]
+    Html,
+    #[serde(alias = "markdown")]
+    Markdown,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RendererConfiguration {
     pub enabled: Option<bool>,
+    // Not needed when only disabling the global renderer for a feed
+    #[serde(default)]
     pub binary: String,
     pub args: Option<Vec<String>>,
+    #[serde(default)]
+    pub input: RendererInput,
+    // Tells the renderer to emit colors even though its output is not a terminal
+    pub force_color: Option<bool>,
+    // Extra environment variables for the renderer
+    pub env: Option<HashMap<String, String>>,
+    // Seconds to wait before giving up on the renderer
+    pub timeout: Option<u64>,
+}
+
+impl RendererConfiguration {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CommandConfiguration {
+    pub binary: String,
+    pub args: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LinksConfiguration {
+    // Receives the link as its last argument
+    #[serde(default = "default_opener")]
+    pub opener: CommandConfiguration,
+    // Receives the link through stdin, OSC 52 is used when unset
+    pub clipboard: Option<CommandConfiguration>,
+}
+
+fn default_opener() -> CommandConfiguration {
+    CommandConfiguration {
+        binary: "xdg-open".to_string(),
+        args: None,
+    }
+}
+
+impl Default for LinksConfiguration {
+    fn default() -> Self {
+        Self {
+            opener: default_opener(),
+            clipboard: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -64,8 +362,9 @@ pub struct ScrollingConfiguration {
     pub y_lines: u16,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone)]
 pub enum SupportedAlignment {
+    #[default]
     #[serde(alias = "left", alias = "default")]
     Left,
     #[serde(alias = "right")]
@@ -74,12 +373,6 @@ pub enum SupportedAlignment {
     Center,
 }
 
-impl Default for SupportedAlignment {
-    fn default() -> Self {
-        Self::Left
-    }
-}
-
 impl From<SupportedAlignment> for ratatui::layout::Alignment {
     fn from(value: SupportedAlignment) -> Self {
         match value {
@@ -95,6 +388,43 @@ pub struct AlignmentConfiguration {
     pub article: SupportedAlignment,
 }
 
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewPosition {
+    #[default]
+    #[serde(alias = "none", alias = "default")]
+    None,
+    #[serde(alias = "bottom")]
+    Bottom,
+    #[serde(alias = "right")]
+    Right,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LayoutConfiguration {
+    // Show a feed list on the left of the entries
+    #[serde(default)]
+    pub sidebar: bool,
+    // Where to show a preview of the highlighted entry, if at all
+    #[serde(default)]
+    pub preview: PreviewPosition,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NavigationConfiguration {
+    // Going past the first or last entry of a feed continues in the previous or next one
+    #[serde(default)]
+    pub across_feeds: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StateConfiguration {
+    // Remember the newest entry of every feed on exit and mark anything newer on the next run
+    #[serde(default)]
+    pub enabled: bool,
+    // Defaults to $XDG_STATE_HOME/juicerss/state.toml
+    pub path: Option<String>,
+}
+
 impl Default for ScrollingConfiguration {
     fn default() -> Self {
         Self {
@@ -108,10 +438,22 @@ impl Default for ScrollingConfiguration {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
+    // Only picks the icon preset when [icons] does not
     #[serde(default)]
     pub nerd_fonts: bool,
     #[serde(default)]
+    pub icons: IconsConfiguration,
+    #[serde(default)]
     pub list_format: ListFormat,
+    // Replaces the list format when set
+    pub list_template: Option<ListTemplate>,
+    #[serde(default)]
+    pub list_styles: HashMap<Field, FieldStyleConfiguration>,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    // Adds the time of day to "iso" and "relative" dates
+    #[serde(default)]
+    pub show_time: bool,
     #[serde(default)]
     pub feeds: Option<Vec<FeedConfigEntry>>,
     #[serde(default)]
@@ -122,6 +464,16 @@ pub struct Config {
     pub scrolling: ScrollingConfiguration,
     #[serde(default)]
     pub alignment: AlignmentConfiguration,
+    #[serde(default)]
+    pub layout: LayoutConfiguration,
+    #[serde(default)]
+    pub sort: SortConfiguration,
+    #[serde(default)]
+    pub links: LinksConfiguration,
+    #[serde(default)]
+    pub navigation: NavigationConfiguration,
+    #[serde(default)]
+    pub state: StateConfiguration,
 }
 
 impl Default for ThemeConfiguration {
@@ -151,11 +503,21 @@ impl Default for Config {
         Self {
             feeds: None,
             nerd_fonts: true,
+            icons: IconsConfiguration::default(),
             list_format: ListFormat::Compact,
+            list_template: None,
+            list_styles: HashMap::new(),
+            date_format: DateFormat::default(),
+            show_time: false,
             theme: ThemeConfiguration::default(),
             renderer: None,
             scrolling: ScrollingConfiguration::default(),
             alignment: AlignmentConfiguration::default(),
+            layout: LayoutConfiguration::default(),
+            sort: SortConfiguration::default(),
+            links: LinksConfiguration::default(),
+            navigation: NavigationConfiguration::default(),
+            state: StateConfiguration::default(),
         }
     }
 }
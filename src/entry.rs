@@ -0,0 +1,228 @@
+use crate::config::DateFormat;
+use chrono::{DateTime, Local, Utc};
+use feed_rs::model::{Entry, Text};
+use std::io::Cursor;
+
+const SNIPPET_LENGTH: usize = 60;
+// Wide enough that the first paragraph of a summary ends up on a single line
+const SNIPPET_RENDER_WIDTH: usize = 5000;
+
+pub const UNTITLED_PLACEHOLDER: &str = "(untitled)";
+pub const NO_DATE_PLACEHOLDER: &str = "no date";
+pub const NO_CONTENT_PLACEHOLDER: &str = "No content available for this entry";
+
+// Lots of RSS 2.0 feeds only have pubDate, Atom feeds usually have both
+pub fn entry_date(entry: &Entry) -> Option<DateTime<Utc>> {
+    entry.published.or(entry.updated)
+}
+
+pub fn format_date(
+    date: DateTime<Utc>,
+    format: &DateFormat,
+    show_time: bool,
+    now: DateTime<Local>,
+) -> String {
+    let date = date.with_timezone(&Local);
+    match format {
+        DateFormat::Iso if show_time => date.format("%Y-%m-%d %H:%M").to_string(),
+        DateFormat::Iso => date.date_naive().to_string(),
+        DateFormat::Relative => {
+            let relative = relative_date(date, now);
+            // Anything under a day is already precise enough
+            if show_time && (now - date).abs() >= chrono::TimeDelta::days(1) {
+                format!("{relative}, {}", date.format("%H:%M"))
+            } else {
+                relative
+            }
+        }
+        DateFormat::Custom(pattern) => date.format(pattern).to_string(),
+    }
+}
+
+// "just now", "5 minutes ago", "in 2 days"...
+fn relative_date(date: DateTime<Local>, now: DateTime<Local>) -> String {
+    let elapsed = now - date;
+    let seconds = elapsed.num_seconds().abs();
+    if seconds < 60 {
+        return "just now".to_string();
+    }
+
+    let (amount, unit) = match seconds {
+        ..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        86_400..604_800 => (seconds / 86_400, "day"),
+        604_800..2_629_746 => (seconds / 604_800, "week"),
+        2_629_746..31_556_952 => (seconds / 2_629_746, "month"),
+        _ => (seconds / 31_556_952, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+
+    if elapsed < chrono::TimeDelta::zero() {
+        format!("in {amount} {unit}{plural}")
+    } else {
+        format!("{amount} {unit}{plural} ago")
+    }
+}
+
+pub fn entry_title(entry: &Entry) -> String {
+    title_or_else(entry, || entry_summary(entry))
+}
+
+// First paragraph of the summary as plain text
+pub fn entry_summary(entry: &Entry) -> Option<String> {
+    entry.summary.as_ref().and_then(text_snippet)
+}
+
+fn title_or_else(entry: &Entry, summary: impl FnOnce() -> Option<String>) -> String {
+    if let Some(title) = entry
+        .title
+        .as_ref()
+        .filter(|t| !t.content.trim().is_empty())
+    {
+        return title.content.clone();
+    }
+
+    if let Some(snippet) = summary() {
+        return snippet;
+    }
+
+    entry.links.first().map_or_else(
+        || UNTITLED_PLACEHOLDER.to_string(),
+        |link| link.href.clone(),
+    )
+}
+
+// Title and summary as lists show them, worked out once since both can go through html2text
+#[derive(Debug, Clone)]
+pub struct ListText {
+    pub title: String,
+    pub summary: Option<String>,
+}
+
+impl ListText {
+    pub fn new(entry: &Entry) -> Self {
+        let summary = entry_summary(entry);
+        Self {
+            title: title_or_else(entry, || summary.clone()),
+            summary,
+        }
+    }
+}
+
+// Link to the entry itself, skipping enclosures
+pub fn entry_link(entry: &Entry) -> Option<&str> {
+    entry
+        .links
+        .iter()
+        .find(|link| link.rel.as_deref() != Some("enclosure"))
+        .map(|link| link.href.as_str())
+}
+
+pub fn entry_content_html(entry: &Entry) -> Option<String> {
+    entry
+        .content
+        .as_ref()
+        .and_then(|content| content.body.clone())
+        .filter(|body| !body.trim().is_empty())
+}
+
+// RSS 2.0 <description> ends up here
+pub fn entry_summary_html(entry: &Entry) -> Option<String> {
+    entry
+        .summary
+        .as_ref()
+        .map(|summary| summary.content.clone())
+        .filter(|body| !body.trim().is_empty())
+}
+
+// HTML body of the entry, falling back to the summary and then to any media description
+pub fn entry_html(entry: &Entry) -> Option<String> {
+    entry_content_html(entry)
+        .or_else(|| entry_summary_html(entry))
+        .or_else(|| {
+            entry
+                .media
+                .iter()
+                .find_map(|media| media.description.as_ref())
+                .map(|description| description.content.clone())
+        })
+        .filter(|body| !body.trim().is_empty())
+}
+
+fn text_snippet(text: &Text) -> Option<String> {
+    let plain =
+        html2text::from_read(Cursor::new(text.content.as_bytes()), SNIPPET_RENDER_WIDTH).ok()?;
+    let first_line = plain.lines().map(str::trim).find(|line| !line.is_empty())?;
+
+    if first_line.chars().count() > SNIPPET_LENGTH {
+        Some(format!(
+            "{}…",
+            first_line.chars().take(SNIPPET_LENGTH).collect::<String>()
+        ))
+    } else {
+        Some(first_line.to_string())
+    }
+}
+
+// Feed with an RSS item for every (guid, title, pubDate), in document order
+#[cfg(test)]
+pub fn test_feed(items: &[(&str, &str, Option<&str>)]) -> feed_rs::model::Feed {
+    let items: String = items
+        .iter()
+        .map(|(id, title, date)| {
+            let date = date.map_or_else(String::new, |date| format!("<pubDate>{date}</pubDate>"));
+            format!("<item><guid>{id}</guid><title>{title}</title>{date}</item>")
+        })
+        .collect();
+    let rss = format!("<rss version=\"2.0\"><channel><title>t</title>{items}</channel></rss>");
+    feed_rs::parser::parse(rss.as_bytes()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn relative(elapsed: TimeDelta) -> String {
+        let now = Local::now();
+        relative_date(now - elapsed, now)
+    }
+
+    #[test]
+    fn recent_dates_are_just_now() {
+        assert_eq!(relative(TimeDelta::seconds(0)), "just now");
+        assert_eq!(relative(TimeDelta::seconds(59)), "just now");
+        assert_eq!(relative(TimeDelta::seconds(-30)), "just now");
+    }
+
+    #[test]
+    fn picks_the_largest_unit() {
+        assert_eq!(relative(TimeDelta::seconds(60)), "1 minute ago");
+        assert_eq!(relative(TimeDelta::minutes(59)), "59 minutes ago");
+        assert_eq!(relative(TimeDelta::hours(1)), "1 hour ago");
+        assert_eq!(relative(TimeDelta::hours(23)), "23 hours ago");
+        assert_eq!(relative(TimeDelta::days(1)), "1 day ago");
+        assert_eq!(relative(TimeDelta::days(6)), "6 days ago");
+        assert_eq!(relative(TimeDelta::days(7)), "1 week ago");
+        assert_eq!(relative(TimeDelta::days(31)), "1 month ago");
+        assert_eq!(relative(TimeDelta::days(366)), "1 year ago");
+        assert_eq!(relative(TimeDelta::days(800)), "2 years ago");
+    }
+
+    #[test]
+    fn future_dates_count_up() {
+        assert_eq!(relative(TimeDelta::hours(-3)), "in 3 hours");
+        assert_eq!(relative(TimeDelta::days(-1)), "in 1 day");
+    }
+
+    #[test]
+    fn relative_dates_only_get_a_time_after_a_day() {
+        let now = Local::now();
+        let format = |elapsed: TimeDelta| {
+            format_date((now - elapsed).to_utc(), &DateFormat::Relative, true, now)
+        };
+
+        assert_eq!(format(TimeDelta::hours(2)), "2 hours ago");
+        assert!(format(TimeDelta::days(2)).starts_with("2 days ago, "));
+    }
+}
@@ -0,0 +1,78 @@
+// Bonuses over the base point every matched character gets
+const CONSECUTIVE_BONUS: i64 = 5;
+const WORD_START_BONUS: i64 = 3;
+// Most points lost for the characters skipped between two matches
+const MAX_GAP_PENALTY: i64 = 3;
+
+// Case-insensitive match of the query characters in order, anywhere in the text
+// Gives the score, higher is better, and the positions of the matched characters
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let mut positions: Vec<usize> = Vec::with_capacity(query.len());
+    let mut score = 0;
+    for (idx, &c) in text.iter().enumerate() {
+        let Some(&wanted) = query.get(positions.len()) else {
+            break;
+        };
+        if c.to_lowercase().next() != Some(wanted) {
+            continue;
+        }
+
+        score += 1;
+        if idx == 0 || !text[idx - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        if let Some(&previous) = positions.last() {
+            let gap = i64::try_from(idx - previous - 1).unwrap_or(i64::MAX);
+            score += if gap == 0 {
+                CONSECUTIVE_BONUS
+            } else {
+                -gap.min(MAX_GAP_PENALTY)
+            };
+        }
+        positions.push(idx);
+    }
+
+    (positions.len() == query.len()).then_some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order() {
+        assert_eq!(
+            fuzzy_match("bn", "Beta News").map(|(_, p)| p),
+            Some(vec![0, 5])
+        );
+        assert!(fuzzy_match("nb", "Beta News").is_none());
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert!(fuzzy_match("BETA", "beta news").is_some());
+        assert!(fuzzy_match("beta", "BETA NEWS").is_some());
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(fuzzy_match("", "anything"), Some((0, Vec::new())));
+    }
+
+    #[test]
+    fn prefers_consecutive_and_word_start_matches() {
+        let score = |query, text| fuzzy_match(query, text).unwrap().0;
+
+        assert!(score("news", "Beta News") > score("news", "nightly ewsletters"));
+        assert!(score("bn", "Beta News") > score("bn", "abandon"));
+    }
+
+    #[test]
+    fn reports_character_positions() {
+        // Positions count characters, not bytes
+        assert_eq!(fuzzy_match("é", "café").map(|(_, p)| p), Some(vec![3]));
+    }
+}
@@ -0,0 +1,347 @@
+use crate::config::Icons;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Back,
+    SwitchPane,
+    Up,
+    Down,
+    PreviousFeed,
+    NextFeed,
+    PreviousGroup,
+    NextGroup,
+    ResetSelection,
+    Timeline,
+    Open,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    ResetPosition,
+    ToggleMetadata,
+    ToggleSummary,
+    Links,
+    PreviousArticle,
+    NextArticle,
+    OpenLink,
+    CopyLink,
+    Help,
+    QuickSwitch,
+    JumpToFeed,
+    JumpToLink,
+    DeleteCharacter,
+}
+
+#[derive(Debug)]
+pub struct Binding {
+    pub keys: &'static [KeyCode],
+    pub action: Action,
+    // Completes "<key> to ..." in footers, capitalized in the help overlay
+    pub description: &'static str,
+    // Listed in the footer of the screen besides the help overlay
+    pub in_footer: bool,
+    // Needs Ctrl held down
+    pub control: bool,
+}
+
+const fn bind(keys: &'static [KeyCode], action: Action, description: &'static str) -> Binding {
+    Binding {
+        keys,
+        action,
+        description,
+        in_footer: false,
+        control: false,
+    }
+}
+
+impl Binding {
+    const fn in_footer(self) -> Self {
+        Self {
+            in_footer: true,
+            ..self
+        }
+    }
+
+    const fn with_control(self) -> Self {
+        Self {
+            control: true,
+            ..self
+        }
+    }
+}
+
+// Available on every screen
+pub const GLOBAL: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('o')],
+        Action::OpenLink,
+        "open the entry link",
+    ),
+    bind(
+        &[KeyCode::Char('y')],
+        Action::CopyLink,
+        "copy the entry link",
+    ),
+    bind(
+        &[KeyCode::Char('p')],
+        Action::QuickSwitch,
+        "go to a feed by name",
+    )
+    .with_control(),
+    bind(&[KeyCode::Char('?')], Action::Help, "show help").in_footer(),
+];
+
+pub const SELECTION: &[Binding] = &[
+    bind(&[KeyCode::Char('q'), KeyCode::Esc], Action::Quit, "quit"),
+    bind(&[KeyCode::Up], Action::Up, "move up"),
+    bind(&[KeyCode::Down], Action::Down, "move down"),
+    bind(
+        &[KeyCode::Left],
+        Action::PreviousFeed,
+        "go to the previous feed",
+    ),
+    bind(&[KeyCode::Right], Action::NextFeed, "go to the next feed"),
+    bind(
+        &[KeyCode::Char('[')],
+        Action::PreviousGroup,
+        "go to the previous group",
+    ),
+    bind(
+        &[KeyCode::Char(']')],
+        Action::NextGroup,
+        "go to the next group",
+    ),
+    bind(
+        &[
+            KeyCode::Char('1'),
+            KeyCode::Char('2'),
+            KeyCode::Char('3'),
+            KeyCode::Char('4'),
+            KeyCode::Char('5'),
+            KeyCode::Char('6'),
+            KeyCode::Char('7'),
+            KeyCode::Char('8'),
+            KeyCode::Char('9'),
+        ],
+        Action::JumpToFeed,
+        "go to the feed with that number in the group",
+    ),
+    bind(&[KeyCode::Tab], Action::SwitchPane, "switch pane"),
+    bind(
+        &[KeyCode::Char('r')],
+        Action::ResetSelection,
+        "reset selection",
+    )
+    .in_footer(),
+    bind(&[KeyCode::Enter], Action::Open, "select").in_footer(),
+    bind(&[KeyCode::Char('t')], Action::Timeline, "show the timeline").in_footer(),
+];
+
+pub const TIMELINE: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('q'), KeyCode::Char('t'), KeyCode::Esc],
+        Action::Back,
+        "go back",
+    ),
+    bind(&[KeyCode::Up], Action::Up, "move up"),
+    bind(&[KeyCode::Down], Action::Down, "move down"),
+    bind(
+        &[KeyCode::Char('r')],
+        Action::ResetSelection,
+        "reset selection",
+    )
+    .in_footer(),
+    bind(&[KeyCode::Enter], Action::Open, "select").in_footer(),
+];
+
+pub const ARTICLE: &[Binding] = &[
+    bind(&[KeyCode::Char('q'), KeyCode::Esc], Action::Back, "go back"),
+    bind(&[KeyCode::Up], Action::ScrollUp, "scroll up"),
+    bind(&[KeyCode::Down], Action::ScrollDown, "scroll down"),
+    bind(&[KeyCode::Left], Action::ScrollLeft, "scroll left"),
+    bind(&[KeyCode::Right], Action::ScrollRight, "scroll right"),
+    bind(&[KeyCode::PageUp], Action::PageUp, "scroll up a page"),
+    bind(&[KeyCode::PageDown], Action::PageDown, "scroll down a page"),
+    bind(&[KeyCode::Home], Action::Top, "jump to the top"),
+    bind(&[KeyCode::End], Action::Bottom, "jump to the bottom"),
+    bind(
+        &[KeyCode::Char('r')],
+        Action::ResetPosition,
+        "reset position",
+    )
+    .in_footer(),
+    bind(
+        &[KeyCode::Char('m')],
+        Action::ToggleMetadata,
+        "toggle details",
+    )
+    .in_footer(),
+    bind(
+        &[KeyCode::Char('s')],
+        Action::ToggleSummary,
+        "switch between summary and content",
+    ),
+    bind(&[KeyCode::Char('l')], Action::Links, "pick a link").in_footer(),
+    bind(
+        &[KeyCode::Char('p')],
+        Action::PreviousArticle,
+        "open the previous article",
+    ),
+    bind(
+        &[KeyCode::Char('n')],
+        Action::NextArticle,
+        "open the next article",
+    ),
+];
+
+// Overlays take over every key while they are open
+pub const SWITCHER: &[Binding] = &[
+    bind(&[KeyCode::Esc], Action::Back, "close").in_footer(),
+    bind(&[KeyCode::Up], Action::Up, "move up"),
+    bind(&[KeyCode::Down], Action::Down, "move down"),
+    bind(
+        &[KeyCode::Backspace],
+        Action::DeleteCharacter,
+        "delete the last character",
+    ),
+    bind(&[KeyCode::Enter], Action::Open, "go to the feed").in_footer(),
+    bind(&[KeyCode::Char('?')], Action::Help, "show help").in_footer(),
+];
+
+pub const LINK_PICKER: &[Binding] = &[
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('q'), KeyCode::Char('l')],
+        Action::Back,
+        "close",
+    )
+    .in_footer(),
+    bind(&[KeyCode::Up], Action::Up, "move up"),
+    bind(&[KeyCode::Down], Action::Down, "move down"),
+    bind(
+        &[
+            KeyCode::Char('1'),
+            KeyCode::Char('2'),
+            KeyCode::Char('3'),
+            KeyCode::Char('4'),
+            KeyCode::Char('5'),
+            KeyCode::Char('6'),
+            KeyCode::Char('7'),
+            KeyCode::Char('8'),
+            KeyCode::Char('9'),
+        ],
+        Action::JumpToLink,
+        "go to the link with that number",
+    ),
+    bind(
+        &[KeyCode::Enter, KeyCode::Char('o')],
+        Action::OpenLink,
+        "open the link",
+    )
+    .in_footer(),
+    bind(&[KeyCode::Char('y')], Action::CopyLink, "copy the link").in_footer(),
+    bind(&[KeyCode::Char('?')], Action::Help, "show help").in_footer(),
+];
+
+pub const HELP: &[Binding] = &[bind(
+    &[
+        KeyCode::Esc,
+        KeyCode::Char('?'),
+        KeyCode::Char('q'),
+        KeyCode::Enter,
+    ],
+    Action::Back,
+    "close",
+)
+.in_footer()];
+
+pub fn action_for(bindings: &[Binding], key: KeyEvent) -> Option<Action> {
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+    bindings
+        .iter()
+        .find(|binding| binding.control == control && binding.keys.contains(&key.code))
+        .map(|binding| binding.action)
+}
+
+// Arrow keys are shown with the glyphs of the icon set
+fn key_label(code: KeyCode, icons: &Icons) -> String {
+    match code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => icons.up.clone(),
+        KeyCode::Down => icons.down.clone(),
+        KeyCode::Left => icons.prev.clone(),
+        KeyCode::Right => icons.next.clone(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Enter => "<Enter>".to_string(),
+        KeyCode::Tab => "<Tab>".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// All keys of a binding, "q, Esc", "1-9" or "Ctrl-p"
+pub fn keys_label(binding: &Binding, icons: &Icons) -> String {
+    let is_range = binding.keys.len() > 2
+        && binding.keys.windows(2).all(|pair| match pair {
+            [KeyCode::Char(a), KeyCode::Char(b)] => u32::from(*a) + 1 == u32::from(*b),
+            _ => false,
+        });
+    if is_range {
+        return format!(
+            "{}-{}",
+            binding_key_label(binding, binding.keys[0], icons),
+            key_label(binding.keys[binding.keys.len() - 1], icons)
+        );
+    }
+
+    binding
+        .keys
+        .iter()
+        .map(|&key| binding_key_label(binding, key, icons))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn binding_key_label(binding: &Binding, key: KeyCode, icons: &Icons) -> String {
+    if binding.control {
+        format!("Ctrl-{}", key_label(key, icons))
+    } else {
+        key_label(key, icons)
+    }
+}
+
+pub fn binding_for(bindings: &'static [Binding], action: Action) -> Option<&'static Binding> {
+    bindings.iter().find(|binding| binding.action == action)
+}
+
+// "<key> to <description>", with the first key of the binding
+pub fn hint(binding: &Binding, icons: &Icons) -> String {
+    format!(
+        "{} to {}",
+        binding_key_label(binding, binding.keys[0], icons),
+        binding.description
+    )
+}
+
+// "r to reset selection, <Enter> to select" for the bindings meant for the footer
+pub fn footer(bindings: &[Binding], icons: &Icons) -> String {
+    footer_of(bindings.iter().chain(GLOBAL), icons)
+}
+
+// Same without the global bindings, for overlays
+pub fn overlay_footer(bindings: &[Binding], icons: &Icons) -> String {
+    footer_of(bindings.iter(), icons)
+}
+
+fn footer_of<'a>(bindings: impl Iterator<Item = &'a Binding>, icons: &Icons) -> String {
+    bindings
+        .filter(|binding| binding.in_footer)
+        .map(|binding| hint(binding, icons))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
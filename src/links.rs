@@ -0,0 +1,54 @@
+use crate::config::{CommandConfiguration, LinksConfiguration};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+pub fn open_link(config: &LinksConfiguration, url: &str) -> Result<(), String> {
+    command_from(&config.opener)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|mut child| {
+            // Openers may outlive the reader, it is reaped whenever it exits
+            std::thread::spawn(move || child.wait());
+        })
+        .map_err(|err| format!("Failed running {}: {err}", config.opener.binary))
+}
+
+// Without a clipboard command the terminal itself is asked to copy via OSC 52
+pub fn copy_to_clipboard(config: &LinksConfiguration, text: &str) -> Result<(), String> {
+    let Some(clipboard) = &config.clipboard else {
+        let mut stdout = std::io::stdout();
+        return write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))
+            .and_then(|()| stdout.flush())
+            .map_err(|err| format!("Failed writing to terminal: {err}"));
+    };
+
+    let mut child = command_from(clipboard)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed running {}: {err}", clipboard.binary))?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| format!("Failed writing to {}", clipboard.binary))?
+        .write_all(text.as_bytes())
+        .map_err(|err| format!("Failed writing to {}: {err}", clipboard.binary))?;
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{} exited with {status}", clipboard.binary)),
+        Err(err) => Err(format!("Failed waiting for {}: {err}", clipboard.binary)),
+    }
+}
+
+fn command_from(config: &CommandConfiguration) -> Command {
+    let mut command = Command::new(&config.binary);
+    command.args(config.args.clone().unwrap_or_default());
+    command
+}
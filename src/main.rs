@@ -1,18 +1,62 @@
 use clap::Parser;
 use feed_rs::model::{Entry, Feed};
 mod config;
+mod entry;
+mod fuzzy;
+mod keymap;
+mod links;
+mod render;
+mod renderer;
+mod state;
+mod template;
 mod tui;
 use config::Config;
 use config::FeedConfigEntry;
+use config::{RendererConfiguration, SortConfiguration, SortKey, SortOrder};
+use entry::ListText;
 use regex::Regex;
+use state::{default_state_path, State};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use tokio::task::JoinSet;
 use tui::App;
 
 #[derive(Debug, Clone)]
 pub struct FeedWithCustom {
-    feed: Feed,
+    // Error message when the feed could not be fetched or parsed
+    feed: Result<Feed, String>,
     filtered_entries: Vec<Entry>,
+    // Title and summary of every filtered entry, in the same order
+    list_texts: Vec<ListText>,
     name: Option<String>,
+    url: String,
+    group: Option<String>,
+    renderer: Option<RendererConfiguration>,
+    // Ids of the entries newer than what was seen on the last run
+    new_entries: HashSet<String>,
+}
+
+impl FeedWithCustom {
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.feed
+                .as_ref()
+                .ok()
+                .and_then(|feed| feed.title.clone())
+                .map_or_else(|| self.url.clone(), |title| title.content)
+        })
+    }
+
+    pub fn is_new(&self, entry: &Entry) -> bool {
+        self.new_entries.contains(&entry.id)
+    }
+
+    pub fn new_count(&self) -> usize {
+        self.filtered_entries
+            .iter()
+            .filter(|entry| self.is_new(entry))
+            .count()
+    }
 }
 
 #[derive(Parser)]
@@ -24,12 +68,38 @@ struct Cli {
     config: Option<String>,
 }
 
-#[derive(Default)]
 struct FeedResponse {
-    body: String,
+    // Position of the feed in the configuration, as responses arrive in any order
+    index: usize,
+    body: Result<String, String>,
     url: String,
     filter: Option<String>,
     name: Option<String>,
+    group: Option<String>,
+    sort: Option<SortConfiguration>,
+    renderer: Option<RendererConfiguration>,
+}
+
+fn sort_entries(entries: &mut [Entry], sort: &SortConfiguration) {
+    match sort.by {
+        SortKey::Original => {}
+        SortKey::Published => entries.sort_by_key(|entry| entry.published.or(entry.updated)),
+        SortKey::Updated => entries.sort_by_key(|entry| entry.updated.or(entry.published)),
+        SortKey::Title => entries.sort_by_cached_key(|entry| {
+            entry
+                .title
+                .as_ref()
+                .map(|title| title.content.to_lowercase())
+        }),
+    }
+
+    if sort.order() == SortOrder::Descending {
+        entries.reverse();
+    }
+}
+
+async fn fetch_body(url: &str) -> Result<String, reqwest::Error> {
+    reqwest::get(url).await?.text().await
 }
 
 #[tokio::main]
@@ -59,35 +129,53 @@ async fn main() -> Result<(), ()> {
         )
     };
 
+    let state_path: Option<PathBuf> = app_config.state.enabled.then(|| {
+        app_config
+            .state
+            .path
+            .clone()
+            .map(PathBuf::from)
+            .or_else(default_state_path)
+            .expect("Could not find where to keep the state file, set state.path")
+    });
+    let mut state = state_path.as_deref().map(State::load);
+
     let mut set = JoinSet::new();
 
     config_feeds
         .expect("No feeds were specified")
         .into_iter()
-        .map(|e| async move {
+        .enumerate()
+        .map(|(index, e)| async move {
             FeedResponse {
-                body: reqwest::get(&e.url).await.unwrap().text().await.unwrap(),
+                index,
+                body: fetch_body(&e.url).await.map_err(|err| err.to_string()),
                 url: e.url,
                 name: e.name,
                 filter: e.filter,
+                group: e.group,
+                sort: e.sort,
+                renderer: e.renderer,
             }
         })
         .for_each(|e| {
             set.spawn(e);
         });
 
-    let feeds: Vec<_> = set
-        .join_all()
-        .await
+    let mut responses = set.join_all().await;
+    responses.sort_by_key(|response| response.index);
+
+    let feeds: Vec<_> = responses
         .iter()
         .map(|response| {
-            let parsed = feed_rs::parser::parse(response.body.as_bytes()).ok();
+            let parsed_feed = response.body.clone().and_then(|body| {
+                feed_rs::parser::parse(body.as_bytes()).map_err(|err| err.to_string())
+            });
 
-            let parsed_feed = parsed?;
-
-            let filtered_entries: Vec<Entry> = parsed_feed
-                .clone()
-                .entries
+            let mut filtered_entries: Vec<Entry> = parsed_feed
+                .as_ref()
+                .map(|feed| feed.entries.clone())
+                .unwrap_or_default()
                 .into_iter()
                 .filter(|entry| {
                     let Some(pattern) = &response.filter else {
@@ -104,16 +192,103 @@ async fn main() -> Result<(), ()> {
                 })
                 .collect();
 
-            Some(FeedWithCustom {
+            sort_entries(
+                &mut filtered_entries,
+                response.sort.as_ref().unwrap_or(&app_config.sort),
+            );
+
+            let list_texts = filtered_entries.iter().map(ListText::new).collect();
+
+            let new_entries = match (&state, &parsed_feed) {
+                (Some(state), Ok(feed)) => state
+                    .feeds
+                    .get(&response.url)
+                    .map(|seen| seen.new_entries(feed))
+                    .unwrap_or_default(),
+                _ => HashSet::new(),
+            };
+
+            FeedWithCustom {
                 feed: parsed_feed,
                 filtered_entries,
+                list_texts,
                 name: response.name.clone(),
-            })
+                url: response.url.clone(),
+                group: response.group.clone(),
+                renderer: response.renderer.clone(),
+                new_entries,
+            }
         })
         .collect();
 
+    // Everything fetched now counts as seen on the next run
+    // Feeds that failed to load keep what was seen of them before
+    if let Some(state) = &mut state {
+        for feed in &feeds {
+            if let Ok(parsed_feed) = &feed.feed {
+                state.record(&feed.url, parsed_feed);
+            }
+        }
+    }
+
+    let fetched_at = chrono::Local::now();
     let terminal = ratatui::init();
-    App::new(feeds, app_config).run(terminal);
+    App::new(feeds, app_config, fetched_at).run(terminal);
     ratatui::restore();
+
+    if let (Some(state), Some(path)) = (&state, &state_path) {
+        if let Err(err) = state.save(path) {
+            eprintln!("Failed saving state file {}: {err}", path.display());
+        }
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::test_feed;
+
+    fn sorted_ids(mut entries: Vec<Entry>, by: SortKey, order: Option<SortOrder>) -> Vec<String> {
+        sort_entries(&mut entries, &SortConfiguration { by, order });
+        entries.into_iter().map(|entry| entry.id).collect()
+    }
+
+    fn entries() -> Vec<Entry> {
+        test_feed(&[
+            ("b", "beta", Some("Sat, 02 Nov 2024 10:00:00 GMT")),
+            ("a", "Alpha", Some("Sun, 03 Nov 2024 10:00:00 GMT")),
+            ("c", "gamma", None),
+        ])
+        .entries
+    }
+
+    #[test]
+    fn original_order_is_kept() {
+        assert_eq!(
+            sorted_ids(entries(), SortKey::Original, None),
+            ["b", "a", "c"]
+        );
+    }
+
+    #[test]
+    fn dates_default_to_newest_first() {
+        assert_eq!(
+            sorted_ids(entries(), SortKey::Published, None),
+            ["a", "b", "c"]
+        );
+        assert_eq!(
+            sorted_ids(entries(), SortKey::Published, Some(SortOrder::Ascending)),
+            ["c", "b", "a"]
+        );
+    }
+
+    #[test]
+    fn titles_ignore_case() {
+        assert_eq!(sorted_ids(entries(), SortKey::Title, None), ["a", "b", "c"]);
+        assert_eq!(
+            sorted_ids(entries(), SortKey::Title, Some(SortOrder::Descending)),
+            ["c", "b", "a"]
+        );
+    }
+}
@@ -0,0 +1,789 @@
+use crate::tui::AppTheme;
+use html2text::render::{RichAnnotation, TaggedLine, TextDecorator};
+use ratatui::{
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+};
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::OnceLock;
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+
+const CODE_THEME: &str = "base16-ocean.dark";
+// Wide enough that preformatted lines are never wrapped
+const PREFORMAT_WIDTH: usize = 5000;
+// Private use character standing in for preformatted blocks while the rest is wrapped
+const CODE_BLOCK_MARKER: char = '\u{E000}';
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderedArticle {
+    pub text: Text<'static>,
+    // Distinct links in order of appearance, referenced in the text as [index + 1]
+    pub links: Vec<String>,
+}
+
+impl RenderedArticle {
+    // Unstyled version of the text, for feeding external renderers
+    pub fn plain(&self) -> String {
+        self.text
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// Same as html2text's RichDecorator, minus the markup characters that styling replaces
+#[derive(Clone, Debug)]
+struct StyledDecorator;
+
+impl TextDecorator for StyledDecorator {
+    type Annotation = RichAnnotation;
+
+    fn decorate_link_start(&mut self, url: &str) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Link(url.to_string()))
+    }
+
+    fn decorate_link_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_em_start(&self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Emphasis)
+    }
+
+    fn decorate_em_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_strong_start(&self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Strong)
+    }
+
+    fn decorate_strong_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_strikeout_start(&self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Strikeout)
+    }
+
+    fn decorate_strikeout_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_code_start(&self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Code)
+    }
+
+    fn decorate_code_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_preformat_first(&self) -> Self::Annotation {
+        RichAnnotation::Preformat(false)
+    }
+
+    fn decorate_preformat_cont(&self) -> Self::Annotation {
+        RichAnnotation::Preformat(true)
+    }
+
+    fn decorate_image(&mut self, src: &str, title: &str) -> (String, Self::Annotation) {
+        (title.to_string(), RichAnnotation::Image(src.to_string()))
+    }
+
+    fn header_prefix(&self, level: usize) -> String {
+        "#".repeat(level) + " "
+    }
+
+    fn quote_prefix(&self) -> String {
+        "> ".to_string()
+    }
+
+    fn unordered_item_prefix(&self) -> String {
+        "* ".to_string()
+    }
+
+    fn ordered_item_prefix(&self, i: i64) -> String {
+        format!("{i}. ")
+    }
+
+    fn make_subblock_decorator(&self) -> Self {
+        Self
+    }
+
+    fn finalise(&mut self, _links: Vec<String>) -> Vec<TaggedLine<Self::Annotation>> {
+        Vec::new()
+    }
+}
+
+// Keeps the inline markup as Markdown, code blocks are fenced afterwards
+#[derive(Clone, Debug, Default)]
+struct MarkdownDecorator {
+    // Targets of the links currently open, written out when they close
+    open_links: Vec<String>,
+}
+
+impl TextDecorator for MarkdownDecorator {
+    type Annotation = RichAnnotation;
+
+    fn decorate_link_start(&mut self, url: &str) -> (String, Self::Annotation) {
+        self.open_links.push(url.to_string());
+        ("[".to_string(), RichAnnotation::Link(url.to_string()))
+    }
+
+    fn decorate_link_end(&mut self) -> String {
+        format!("]({})", self.open_links.pop().unwrap_or_default())
+    }
+
+    fn decorate_em_start(&self) -> (String, Self::Annotation) {
+        ("*".to_string(), RichAnnotation::Emphasis)
+    }
+
+    fn decorate_em_end(&self) -> String {
+        "*".to_string()
+    }
+
+    fn decorate_strong_start(&self) -> (String, Self::Annotation) {
+        ("**".to_string(), RichAnnotation::Strong)
+    }
+
+    fn decorate_strong_end(&self) -> String {
+        "**".to_string()
+    }
+
+    fn decorate_strikeout_start(&self) -> (String, Self::Annotation) {
+        ("~~".to_string(), RichAnnotation::Strikeout)
+    }
+
+    fn decorate_strikeout_end(&self) -> String {
+        "~~".to_string()
+    }
+
+    // Backticks are added afterwards, code inside <pre> would get them too
+    fn decorate_code_start(&self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Code)
+    }
+
+    fn decorate_code_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_preformat_first(&self) -> Self::Annotation {
+        RichAnnotation::Preformat(false)
+    }
+
+    fn decorate_preformat_cont(&self) -> Self::Annotation {
+        RichAnnotation::Preformat(true)
+    }
+
+    fn decorate_image(&mut self, src: &str, title: &str) -> (String, Self::Annotation) {
+        (
+            format!("![{title}]({src})"),
+            RichAnnotation::Image(src.to_string()),
+        )
+    }
+
+    fn header_prefix(&self, level: usize) -> String {
+        "#".repeat(level) + " "
+    }
+
+    fn quote_prefix(&self) -> String {
+        "> ".to_string()
+    }
+
+    fn unordered_item_prefix(&self) -> String {
+        "* ".to_string()
+    }
+
+    fn ordered_item_prefix(&self, i: i64) -> String {
+        format!("{i}. ")
+    }
+
+    fn make_subblock_decorator(&self) -> Self {
+        Self::default()
+    }
+
+    fn finalise(&mut self, _links: Vec<String>) -> Vec<TaggedLine<Self::Annotation>> {
+        Vec::new()
+    }
+}
+
+// Converts HTML into Markdown, for external renderers that are better at it than at plain text
+pub fn html_to_markdown(html: &str, width: usize) -> Result<String, String> {
+    let (prose_html, code_blocks) = extract_code_blocks(html);
+    let languages = code_block_languages(html);
+    let lines = markdown_lines(&prose_html, width)?;
+
+    let mut markdown: Vec<String> = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let strings: Vec<&str> = line.tagged_strings().map(|s| s.s.as_str()).collect();
+        let Some((marker_idx, marker_start, block_idx)) =
+            code_block_marker(&strings).filter(|&(_, _, block_idx)| block_idx < code_blocks.len())
+        else {
+            markdown.push(markdown_text(line));
+            continue;
+        };
+
+        // Quotes stay quoted on every line of the fence, list markers become indentation
+        let prefix = strings[..marker_idx].concat() + &strings[marker_idx][..marker_start];
+        let indentation: String = prefix
+            .chars()
+            .map(|c| if c == '>' { c } else { ' ' })
+            .collect();
+        let language = languages.get(block_idx).cloned().flatten();
+
+        markdown.push(format!("{prefix}```{}", language.unwrap_or_default()));
+        let mut block_lines: Vec<String> =
+            markdown_lines(&code_blocks[block_idx], PREFORMAT_WIDTH)?
+                .iter()
+                .map(markdown_text)
+                .collect();
+        while block_lines.last().is_some_and(String::is_empty) {
+            block_lines.pop();
+        }
+        markdown.extend(block_lines.into_iter().map(|text| {
+            if text.is_empty() {
+                indentation.trim_end().to_string()
+            } else {
+                format!("{indentation}{text}")
+            }
+        }));
+        markdown.push(format!("{indentation}```"));
+    }
+
+    Ok(markdown.join("\n"))
+}
+
+fn markdown_lines(
+    html: &str,
+    width: usize,
+) -> Result<Vec<TaggedLine<Vec<RichAnnotation>>>, String> {
+    html2text::config::with_decorator(MarkdownDecorator::default())
+        .allow_width_overflow()
+        .lines_from_read(Cursor::new(html.as_bytes()), width)
+        .map_err(|err| err.to_string())
+}
+
+// Inline code gets its backticks here, code blocks are fenced instead
+fn markdown_text(line: &TaggedLine<Vec<RichAnnotation>>) -> String {
+    let preformatted = line.tagged_strings().any(|s| is_preformatted(&s.tag));
+    line.tagged_strings()
+        .map(|s| {
+            if !preformatted && s.tag.contains(&RichAnnotation::Code) {
+                format!("`{}`", s.s)
+            } else {
+                s.s.clone()
+            }
+        })
+        .collect()
+}
+
+struct Segment<'a> {
+    line_idx: usize,
+    text: Cow<'a, str>,
+    annotations: &'a [RichAnnotation],
+    // Number of the link this segment closes, if any
+    link_number: Option<usize>,
+    // Index of the code block the segment comes from
+    code_block: Option<usize>,
+}
+
+// Renders HTML into styled text where each link is followed by its number, with the full list at the end
+// Text is wrapped at width, preformatted blocks are kept as they are and may be wider
+pub fn render_html(html: &str, width: usize, theme: &AppTheme) -> Result<RenderedArticle, String> {
+    let (prose_html, code_blocks) = extract_code_blocks(html);
+    let lines = styled_lines(&prose_html, width)?;
+    let code_block_lines = code_blocks
+        .iter()
+        .map(|block| {
+            let mut block_lines = styled_lines(block, PREFORMAT_WIDTH)?;
+            while block_lines
+                .last()
+                .is_some_and(|line| line.chars().next().is_none())
+            {
+                block_lines.pop();
+            }
+            Ok(block_lines)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Flattened so that links wrapping over several lines only get numbered once
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut line_count = 0;
+    for line in &lines {
+        let strings: Vec<_> = line.tagged_strings().collect();
+
+        let Some((marker_idx, marker_start, block_idx)) = code_block_marker(
+            &strings
+                .iter()
+                .map(|segment| segment.s.as_str())
+                .collect::<Vec<_>>(),
+        )
+        .filter(|&(_, _, block_idx)| block_idx < code_block_lines.len()) else {
+            segments.extend(strings.iter().map(|segment| Segment {
+                line_idx: line_count,
+                text: Cow::Borrowed(segment.s.as_str()),
+                annotations: &segment.tag,
+                link_number: None,
+                code_block: None,
+            }));
+            line_count += 1;
+            continue;
+        };
+
+        // Whatever comes before the marker, like quotes or list markers, starts the first line
+        let prefix: Vec<(&str, &[RichAnnotation])> = strings[..marker_idx]
+            .iter()
+            .map(|segment| (segment.s.as_str(), segment.tag.as_slice()))
+            .chain(std::iter::once((
+                &strings[marker_idx].s[..marker_start],
+                strings[marker_idx].tag.as_slice(),
+            )))
+            .filter(|(text, _)| !text.is_empty())
+            .collect();
+
+        for (code_line_idx, code_line) in code_block_lines[block_idx].iter().enumerate() {
+            // Blank lines keep no prefix, they would end the code block early otherwise
+            if code_line.chars().next().is_some() {
+                segments.extend(prefix.iter().map(|&(text, annotations)| Segment {
+                    line_idx: line_count,
+                    // Quotes stay on the following lines, list markers become indentation
+                    text: if code_line_idx == 0 {
+                        Cow::Borrowed(text)
+                    } else {
+                        Cow::Owned(
+                            text.chars()
+                                .map(|c| if c == '>' { c } else { ' ' })
+                                .collect(),
+                        )
+                    },
+                    annotations,
+                    link_number: None,
+                    code_block: Some(block_idx),
+                }));
+                segments.extend(code_line.tagged_strings().map(|segment| Segment {
+                    line_idx: line_count,
+                    text: Cow::Borrowed(segment.s.as_str()),
+                    annotations: &segment.tag,
+                    link_number: None,
+                    code_block: Some(block_idx),
+                }));
+            }
+            line_count += 1;
+        }
+    }
+
+    let mut links: Vec<String> = Vec::new();
+    for idx in 0..segments.len() {
+        let Some(link) = link_of(segments[idx].annotations) else {
+            continue;
+        };
+        if segments
+            .get(idx + 1)
+            .is_some_and(|next| link_of(next.annotations) == Some(link))
+        {
+            continue;
+        }
+
+        let number = links
+            .iter()
+            .position(|known| known == link)
+            .unwrap_or_else(|| {
+                links.push(link.to_string());
+                links.len() - 1
+            })
+            + 1;
+        segments[idx].link_number = Some(number);
+    }
+
+    let languages = code_block_languages(html);
+    // Highlighting carries state from line to line, so there is one per code block
+    let mut highlighter: Option<(usize, Option<HighlightLines>)> = None;
+    let mut rendered_lines: Vec<Line<'static>> = Vec::with_capacity(line_count);
+
+    for (line_idx, line_segments) in segments
+        .chunk_by(|a, b| a.line_idx == b.line_idx)
+        .map(|chunk| (chunk[0].line_idx, chunk))
+    {
+        // html2text skips empty lines when flattening, keep them
+        rendered_lines.resize(line_idx, Line::default());
+
+        if let Some(block_idx) = line_segments[0].code_block {
+            if highlighter.as_ref().map(|(idx, _)| *idx) != Some(block_idx) {
+                let language = languages.get(block_idx).cloned().flatten();
+                highlighter = Some((
+                    block_idx,
+                    language.and_then(|language| code_highlighter(&language)),
+                ));
+            }
+        }
+        let mut block_highlighter = highlighter
+            .as_mut()
+            .filter(|(idx, _)| Some(*idx) == line_segments[0].code_block)
+            .and_then(|(_, highlighter)| highlighter.as_mut());
+
+        // Only the prefixes StyledDecorator puts in front of headings and quotes count
+        let line_style = match line_segments[0].text.as_ref() {
+            prefix if is_heading_prefix(prefix) => theme.accent.bold(),
+            "> " => theme.text.italic(),
+            _ => theme.text,
+        };
+
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        let mut code_buffer = String::new();
+
+        for segment in line_segments {
+            if is_preformatted(segment.annotations) {
+                code_buffer.push_str(&segment.text);
+                continue;
+            }
+            flush_code(
+                &mut code_buffer,
+                &mut spans,
+                block_highlighter.as_deref_mut(),
+                theme,
+            );
+
+            spans.push(Span::styled(
+                segment.text.to_string(),
+                segment_style(segment.annotations, line_style, theme),
+            ));
+            if let Some(number) = segment.link_number {
+                spans.push(Span::styled(format!("[{number}]"), theme.accent));
+            }
+        }
+        flush_code(&mut code_buffer, &mut spans, block_highlighter, theme);
+
+        rendered_lines.push(Line::from(spans));
+    }
+    rendered_lines.resize(line_count, Line::default());
+
+    if !links.is_empty() {
+        rendered_lines.push(Line::default());
+        rendered_lines.extend(links.iter().enumerate().map(|(idx, link)| {
+            Line::from(vec![
+                Span::styled(format!("[{}]: ", idx + 1), theme.accent),
+                Span::styled(link.clone(), theme.accent.underlined()),
+            ])
+        }));
+    }
+
+    Ok(RenderedArticle {
+        text: Text::from(rendered_lines),
+        links,
+    })
+}
+
+fn styled_lines(html: &str, width: usize) -> Result<Vec<TaggedLine<Vec<RichAnnotation>>>, String> {
+    html2text::config::with_decorator(StyledDecorator)
+        .allow_width_overflow()
+        .lines_from_read(Cursor::new(html.as_bytes()), width)
+        .map_err(|err| err.to_string())
+}
+
+// html2text wraps preformatted text and loses whitespace doing so, these blocks get swapped for
+// numbered markers and rendered on their own without a width limit
+fn extract_code_blocks(html: &str) -> (String, Vec<String>) {
+    static PRE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let pre_block = PRE_BLOCK.get_or_init(|| Regex::new(r"(?is)<pre\b.*?</pre\s*>").unwrap());
+    // Written out or as a character reference
+    let marker = MARKER.get_or_init(|| {
+        Regex::new(&format!("{CODE_BLOCK_MARKER}|(?i)&#(?:x0*e000|0*57344);")).unwrap()
+    });
+
+    let mut blocks = Vec::new();
+    let mut prose = String::with_capacity(html.len());
+    let mut prose_start = 0;
+    for block in pre_block.find_iter(html) {
+        // Markers already in the prose would be mistaken for the real ones, code blocks can keep theirs
+        prose.push_str(&marker.replace_all(&html[prose_start..block.start()], ""));
+        prose.push_str(&format!(
+            "<p>{CODE_BLOCK_MARKER}{}{CODE_BLOCK_MARKER}</p>",
+            blocks.len()
+        ));
+        blocks.push(block.as_str().to_string());
+        prose_start = block.end();
+    }
+    prose.push_str(&marker.replace_all(&html[prose_start..], ""));
+
+    (prose, blocks)
+}
+
+// Position of a code block marker as (segment, byte offset in it, block index)
+fn code_block_marker(texts: &[&str]) -> Option<(usize, usize, usize)> {
+    texts.iter().enumerate().find_map(|(idx, text)| {
+        let start = text.find(CODE_BLOCK_MARKER)?;
+        let block_idx = text[start + CODE_BLOCK_MARKER.len_utf8()..]
+            .split(CODE_BLOCK_MARKER)
+            .next()?
+            .parse()
+            .ok()?;
+        Some((idx, start, block_idx))
+    })
+}
+
+fn segment_style(annotations: &[RichAnnotation], base: Style, theme: &AppTheme) -> Style {
+    annotations
+        .iter()
+        .fold(base, |style, annotation| match annotation {
+            RichAnnotation::Emphasis => style.italic(),
+            RichAnnotation::Strong => style.bold(),
+            RichAnnotation::Strikeout => style.crossed_out(),
+            RichAnnotation::Code => style.patch(theme.accent),
+            RichAnnotation::Link(_) => style.patch(theme.accent).underlined(),
+            RichAnnotation::Image(_) => style.patch(theme.accent).italic(),
+            _ => style,
+        })
+}
+
+fn flush_code(
+    code: &mut String,
+    spans: &mut Vec<Span<'static>>,
+    highlighter: Option<&mut HighlightLines>,
+    theme: &AppTheme,
+) {
+    if code.is_empty() {
+        return;
+    }
+
+    code.push('\n');
+    let highlighted =
+        highlighter.and_then(|highlighter| highlighter.highlight_line(code, syntax_set()).ok());
+
+    match highlighted {
+        Some(ranges) => spans.extend(ranges.into_iter().filter_map(|(style, text)| {
+            let text = text.trim_end_matches('\n');
+            (!text.is_empty()).then(|| Span::styled(text.to_string(), syntect_style(style)))
+        })),
+        None => spans.push(Span::styled(
+            code.trim_end_matches('\n').to_string(),
+            theme.accent,
+        )),
+    }
+
+    code.clear();
+}
+
+fn syntect_style(style: syntect::highlighting::Style) -> Style {
+    let mut converted = Style::new().fg(Color::Rgb(
+        style.foreground.r,
+        style.foreground.g,
+        style.foreground.b,
+    ));
+    if style.font_style.contains(FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        converted = converted.add_modifier(Modifier::UNDERLINED);
+    }
+    converted
+}
+
+fn code_highlighter(language: &str) -> Option<HighlightLines<'static>> {
+    let syntax = syntax_set().find_syntax_by_token(language)?;
+    Some(HighlightLines::new(syntax, code_theme()))
+}
+
+// Languages of every <pre> block in document order, html2text does not keep them around
+fn code_block_languages(html: &str) -> Vec<Option<String>> {
+    static PRE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    static LANGUAGE: OnceLock<Regex> = OnceLock::new();
+
+    let pre_block = PRE_BLOCK.get_or_init(|| {
+        Regex::new(
+            r#"(?is)(?:<div\b[^>]*\bhighlight-source-([\w+#.-]+)[^>]*>\s*)?<pre\b([^>]*)>(?:\s*<code\b([^>]*)>)?"#,
+        )
+        .unwrap()
+    });
+    let language = LANGUAGE.get_or_init(|| {
+        Regex::new(r#"(?i)(?:\b(?:language|lang)-([\w+#.-]+)|\blang\s*=\s*["']([^"']+))"#).unwrap()
+    });
+
+    pre_block
+        .captures_iter(html)
+        .map(|block| {
+            if let Some(source) = block.get(1) {
+                return Some(source.as_str().to_string());
+            }
+
+            [block.get(2), block.get(3)]
+                .into_iter()
+                .flatten()
+                .find_map(|attributes| language.captures(attributes.as_str()))
+                .and_then(|found| found.get(1).or_else(|| found.get(2)))
+                .map(|found| found.as_str().to_string())
+        })
+        .collect()
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn code_theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| ThemeSet::load_defaults().themes.remove(CODE_THEME).unwrap())
+}
+
+// "# ", "## "... as returned by header_prefix
+fn is_heading_prefix(text: &str) -> bool {
+    text.strip_suffix(' ')
+        .is_some_and(|hashes| !hashes.is_empty() && hashes.chars().all(|c| c == '#'))
+}
+
+fn is_preformatted(annotations: &[RichAnnotation]) -> bool {
+    annotations
+        .iter()
+        .any(|annotation| matches!(annotation, RichAnnotation::Preformat(_)))
+}
+
+fn link_of(annotations: &[RichAnnotation]) -> Option<&str> {
+    annotations.iter().find_map(|annotation| match annotation {
+        RichAnnotation::Link(url) => Some(url.as_str()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(html: &str, width: usize) -> RenderedArticle {
+        let theme = AppTheme {
+            accent: Style::new(),
+            text: Style::new(),
+            error: Style::new(),
+        };
+        render_html(html, width, &theme).unwrap()
+    }
+
+    #[test]
+    fn swaps_code_blocks_for_numbered_markers() {
+        let (prose, blocks) =
+            extract_code_blocks("<p>a</p><pre>x</pre><p>b</p><PRE class=\"y\">\nz</pre >");
+
+        assert_eq!(
+            prose,
+            "<p>a</p><p>\u{E000}0\u{E000}</p><p>b</p><p>\u{E000}1\u{E000}</p>"
+        );
+        assert_eq!(blocks, ["<pre>x</pre>", "<PRE class=\"y\">\nz</pre >"]);
+    }
+
+    #[test]
+    fn drops_markers_already_in_the_prose() {
+        let (prose, blocks) =
+            extract_code_blocks("<p>\u{E000}0\u{E000} &#xE000;&#57344;</p><pre>\u{E000}</pre>");
+
+        assert_eq!(prose, "<p>0 </p><p>\u{E000}0\u{E000}</p>");
+        assert_eq!(blocks, ["<pre>\u{E000}</pre>"]);
+        assert_eq!(
+            plain("<p>literal \u{E000}0\u{E000} marker</p><pre>real</pre>", 80).plain(),
+            "literal 0 marker\n\nreal"
+        );
+    }
+
+    #[test]
+    fn finds_markers_after_prefixes() {
+        assert_eq!(code_block_marker(&["\u{E000}3\u{E000}"]), Some((0, 0, 3)));
+        assert_eq!(
+            code_block_marker(&["> ", "* \u{E000}12\u{E000}"]),
+            Some((1, 2, 12))
+        );
+        assert_eq!(code_block_marker(&["no marker"]), None);
+        assert_eq!(code_block_marker(&["\u{E000}x\u{E000}"]), None);
+    }
+
+    #[test]
+    fn numbers_every_link_once() {
+        let rendered = plain(
+            "<p><a href=\"https://a\">one</a>, <a href=\"https://b\">two</a>, \
+             <a href=\"https://a\">again</a></p>",
+            80,
+        );
+
+        assert_eq!(rendered.links, ["https://a", "https://b"]);
+        assert_eq!(
+            rendered.plain(),
+            "one[1], two[2], again[1]\n\n[1]: https://a\n[2]: https://b"
+        );
+    }
+
+    #[test]
+    fn numbers_wrapped_links_at_their_end() {
+        let rendered = plain(
+            "<p><a href=\"https://a\">a long link that wraps</a></p>",
+            10,
+        );
+
+        assert_eq!(rendered.links, ["https://a"]);
+        assert_eq!(rendered.plain().matches("[1]").count(), 2);
+        assert!(rendered.plain().contains("wraps[1]"));
+    }
+
+    #[test]
+    fn keeps_code_blocks_unwrapped() {
+        assert_eq!(
+            plain(
+                "<p>Some text</p><pre>let long_line = 1;\n    indented\n\n</pre>",
+                8
+            )
+            .plain(),
+            "Some\ntext\n\nlet long_line = 1;\n    indented"
+        );
+    }
+
+    #[test]
+    fn indents_code_blocks_in_lists_and_quotes() {
+        assert_eq!(
+            plain("<ul><li><pre>x\n  y\n\nz</pre></li></ul>", 80).plain(),
+            "* x\n    y\n\n  z"
+        );
+        assert_eq!(
+            plain("<blockquote><pre>x\n  y</pre></blockquote>", 80).plain(),
+            "> x\n>   y"
+        );
+    }
+
+    #[test]
+    fn fences_every_code_block_with_its_language() {
+        let html = "<p>Use <code>x</code></p>\
+            <pre><code class=\"language-rust\">fn main() {}</code></pre>\
+            <blockquote><pre class=\"lang-python\">a\n\nb</pre></blockquote>\
+            <ul><li><pre>make\nrun</pre></li></ul>";
+
+        assert_eq!(
+            html_to_markdown(html, 80).unwrap(),
+            "Use `x`\n\n```rust\nfn main() {}\n```\n\n> ```python\n> a\n>\n> b\n> ```\n\n\
+             * ```\n  make\n  run\n  ```"
+        );
+    }
+
+    #[test]
+    fn writes_inline_markup_as_markdown() {
+        assert_eq!(
+            html_to_markdown(
+                "<h2>Title</h2><p><em>a</em> <strong>b</strong> <a href=\"https://c\">c</a></p>",
+                80
+            )
+            .unwrap(),
+            "## Title\n\n*a* **b** [c](https://c)"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config::RendererConfiguration;
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const WAIT_INTERVAL: Duration = Duration::from_millis(20);
+
+pub type RendererResult = Result<Vec<u8>, String>;
+
+// Renderer running in the background, it is killed when this is dropped before it is done
+#[derive(Debug)]
+pub struct RendererHandle {
+    // Stdout or an error message, once the renderer is done
+    pub receiver: Receiver<RendererResult>,
+    _cancel: Sender<()>,
+}
+
+// Runs the renderer on its own thread
+pub fn spawn_renderer(
+    renderer: RendererConfiguration,
+    input: String,
+    width: u16,
+) -> RendererHandle {
+    let (sender, receiver) = mpsc::channel();
+    let (cancel, cancelled) = mpsc::channel();
+
+    thread::spawn(move || {
+        // Nobody is waiting for the result anymore if this fails
+        sender
+            .send(run_renderer(&renderer, input, width, &cancelled))
+            .ok();
+    });
+
+    RendererHandle {
+        receiver,
+        _cancel: cancel,
+    }
+}
+
+fn run_renderer(
+    renderer: &RendererConfiguration,
+    input: String,
+    width: u16,
+    cancelled: &Receiver<()>,
+) -> RendererResult {
+    let mut command = Command::new(&renderer.binary);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .args(renderer.args.clone().unwrap_or_default())
+        // Width of the article pane, for renderers that wrap their output
+        .env("COLUMNS", width.to_string())
+        .envs(renderer.env.clone().unwrap_or_default());
+    if renderer.force_color.unwrap_or(false) {
+        command.envs([("CLICOLOR_FORCE", "1"), ("COLORTERM", "truecolor")]);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("Failed running {}: {err}", renderer.binary))?;
+
+    // Written and read concurrently so big articles cannot fill up the pipes and deadlock
+    let mut stdin = child.stdin.take();
+    let writer = thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            stdin.write_all(input.as_bytes()).ok();
+        }
+    });
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let timeout = Duration::from_secs(renderer.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let status = wait_with_timeout(&mut child, timeout, cancelled).map_err(|err| {
+        child.kill().ok();
+        child.wait().ok();
+        format!("{} {err}", renderer.binary)
+    })?;
+
+    writer.join().ok();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!(
+            "{} exited with {status}\n{}",
+            renderer.binary,
+            String::from_utf8_lossy(&stderr).trim_end()
+        ))
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buffer).ok();
+        }
+        buffer
+    })
+}
+
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+    cancelled: &Receiver<()>,
+) -> Result<std::process::ExitStatus, String> {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                return Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) if cancelled.try_recv() == Err(TryRecvError::Disconnected) => {
+                return Err("was cancelled".to_string());
+            }
+            Ok(None) => thread::sleep(WAIT_INTERVAL),
+            Err(err) => return Err(format!("could not be waited for: {err}")),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::entry::entry_date;
+use chrono::{DateTime, Utc};
+use feed_rs::model::Feed;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// What was seen of every feed the last time the reader was closed, by feed URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub feeds: HashMap<String, FeedState>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct FeedState {
+    // Newest entry by date, or the first one of the feed when none have dates
+    pub newest_id: Option<String>,
+    pub newest_date: Option<DateTime<Utc>>,
+}
+
+impl FeedState {
+    fn from_feed(feed: &Feed) -> Self {
+        let newest = feed
+            .entries
+            .iter()
+            .filter(|entry| entry_date(entry).is_some())
+            .max_by_key(|entry| entry_date(entry))
+            .or_else(|| feed.entries.first());
+
+        Self {
+            newest_id: newest.map(|entry| entry.id.clone()),
+            newest_date: newest.and_then(entry_date),
+        }
+    }
+
+    // Ids of the entries that came out after the ones seen last time
+    pub fn new_entries(&self, feed: &Feed) -> HashSet<String> {
+        // The feed had no entries back then, so all of them are new
+        let Some(seen_id) = &self.newest_id else {
+            return feed.entries.iter().map(|entry| entry.id.clone()).collect();
+        };
+
+        // Whatever the feed lists before the last seen entry is new, unless dates say otherwise
+        let seen_position = feed
+            .entries
+            .iter()
+            .position(|entry| entry.id == *seen_id)
+            .unwrap_or(0);
+        feed.entries
+            .iter()
+            .enumerate()
+            .filter(|(idx, entry)| match (self.newest_date, entry_date(entry)) {
+                (Some(seen_date), Some(date)) => date > seen_date,
+                _ => *idx < seen_position,
+            })
+            .map(|(_, entry)| entry.id.clone())
+            .collect()
+    }
+}
+
+impl State {
+    // A missing or broken file is the same as an empty one, it is only written on exit
+    pub fn load(path: &Path) -> Self {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                eprintln!("Failed reading state file {}: {err}", path.display());
+                return Self::default();
+            }
+        };
+
+        toml::from_str(&content).unwrap_or_else(|err| {
+            eprintln!(
+                "Failed parsing state file {}, starting over: {err}",
+                path.display()
+            );
+            Self::default()
+        })
+    }
+
+    pub fn record(&mut self, url: &str, feed: &Feed) {
+        self.feeds
+            .insert(url.to_string(), FeedState::from_feed(feed));
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let content = toml::to_string(self).map_err(|err| err.to_string())?;
+
+        // Written next to it first, so that the file is never left half written
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, content).map_err(|err| err.to_string())?;
+        std::fs::rename(&temporary, path).map_err(|err| err.to_string())
+    }
+}
+
+// $XDG_STATE_HOME/juicerss/state.toml, falling back to ~/.local/state
+pub fn default_state_path() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    Some(state_home.join("juicerss").join("state.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::test_feed;
+
+    // Items as (guid, pubDate), titled after their guid
+    fn feed(items: &[(&str, Option<&str>)]) -> Feed {
+        let items: Vec<_> = items.iter().map(|&(id, date)| (id, id, date)).collect();
+        test_feed(&items)
+    }
+
+    fn sorted(ids: HashSet<String>) -> Vec<String> {
+        let mut ids: Vec<String> = ids.into_iter().collect();
+        ids.sort();
+        ids
+    }
+
+    const MONDAY: &str = "Mon, 04 Nov 2024 10:00:00 GMT";
+    const TUESDAY: &str = "Tue, 05 Nov 2024 10:00:00 GMT";
+    const WEDNESDAY: &str = "Wed, 06 Nov 2024 10:00:00 GMT";
+
+    #[test]
+    fn remembers_the_newest_dated_entry() {
+        let seen = FeedState::from_feed(&feed(&[("a", Some(MONDAY)), ("b", Some(TUESDAY))]));
+        assert_eq!(seen.newest_id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn entries_after_the_newest_seen_date_are_new() {
+        let seen = FeedState::from_feed(&feed(&[("a", Some(MONDAY)), ("b", Some(TUESDAY))]));
+        let now = feed(&[
+            ("c", Some(WEDNESDAY)),
+            ("b", Some(TUESDAY)),
+            ("a", Some(MONDAY)),
+            ("undated", None),
+        ]);
+
+        assert_eq!(sorted(seen.new_entries(&now)), vec!["c"]);
+    }
+
+    #[test]
+    fn undated_feeds_use_the_position_of_the_last_seen_entry() {
+        let seen = FeedState::from_feed(&feed(&[("b", None), ("a", None)]));
+        assert_eq!(seen.newest_id.as_deref(), Some("b"));
+
+        let now = feed(&[("d", None), ("c", None), ("b", None), ("a", None)]);
+        assert_eq!(sorted(seen.new_entries(&now)), vec!["c", "d"]);
+    }
+
+    #[test]
+    fn nothing_is_new_when_the_last_seen_entry_is_gone() {
+        let seen = FeedState::from_feed(&feed(&[("b", None)]));
+        assert!(seen
+            .new_entries(&feed(&[("d", None), ("c", None)]))
+            .is_empty());
+    }
+
+    #[test]
+    fn undated_entries_before_the_last_seen_one_are_new() {
+        let seen = FeedState::from_feed(&feed(&[("a", Some(MONDAY))]));
+        let now = feed(&[("undated", None), ("a", Some(MONDAY)), ("old", None)]);
+
+        assert_eq!(sorted(seen.new_entries(&now)), vec!["undated"]);
+    }
+
+    #[test]
+    fn everything_is_new_for_feeds_seen_empty() {
+        let seen = FeedState::from_feed(&feed(&[]));
+        let now = feed(&[("a", Some(MONDAY)), ("b", None)]);
+
+        assert_eq!(sorted(seen.new_entries(&now)), vec!["a", "b"]);
+    }
+}
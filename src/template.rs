@@ -0,0 +1,413 @@
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// The two built-in list formats, written as templates
+pub const COMPACT: &str = "{?{new} }{date_icon} {date} {title_icon} {?[{feed}] }{title}";
+pub const EXTENDED: &str = "{?{new} }{date_icon} {date}\n{title_icon} {?[{feed}] }{title}\n";
+
+// Everything about an entry a list template can show
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
+pub enum Field {
+    New,
+    DateIcon,
+    TitleIcon,
+    Date,
+    Published,
+    Updated,
+    Title,
+    Feed,
+    Author,
+    Categories,
+    Summary,
+    Link,
+}
+
+impl TryFrom<String> for Field {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(match value.as_str() {
+            "new" => Self::New,
+            "date_icon" => Self::DateIcon,
+            "title_icon" => Self::TitleIcon,
+            "date" => Self::Date,
+            "published" => Self::Published,
+            "updated" => Self::Updated,
+            "title" => Self::Title,
+            "feed" => Self::Feed,
+            "author" => Self::Author,
+            "categories" => Self::Categories,
+            "summary" => Self::Summary,
+            "link" => Self::Link,
+            _ => return Err(format!("unknown field \"{value}\"")),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    // {field}, {field:20} or {field:>20}, padded or truncated to the width
+    Field {
+        field: Field,
+        width: Option<usize>,
+        alignment: Alignment,
+    },
+    // {?...}, left out entirely when any field inside it is empty
+    Optional(Vec<Segment>),
+}
+
+// How a list entry is laid out, like "{date} {title} [{author}]"
+// Newlines in it spread an entry over several lines, {{ and }} are literal braces
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ListTemplate {
+    segments: Vec<Segment>,
+}
+
+impl TryFrom<String> for ListTemplate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let segments = parse_segments(&mut value.chars().peekable(), false)
+            .map_err(|err| format!("invalid list template \"{value}\": {err}"))?;
+        Ok(Self { segments })
+    }
+}
+
+impl ListTemplate {
+    pub fn render(
+        &self,
+        value: impl Fn(Field) -> String,
+        styles: &HashMap<Field, Style>,
+    ) -> Vec<Line<'static>> {
+        let mut pieces = Vec::new();
+        render_segments(&self.segments, &value, styles, &mut pieces);
+
+        let mut lines = vec![Line::default()];
+        for (text, style) in pieces {
+            for (idx, part) in text.split('\n').enumerate() {
+                if idx > 0 {
+                    lines.push(Line::default());
+                }
+                if !part.is_empty() {
+                    if let Some(line) = lines.last_mut() {
+                        line.push_span(Span::styled(part.to_string(), style));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+fn parse_segments(chars: &mut Peekable<Chars>, nested: bool) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            None if nested => return Err("\"{?\" is never closed".to_string()),
+            None => break,
+            Some('{') if chars.next_if_eq(&'{').is_some() => text.push('{'),
+            Some('}') if chars.next_if_eq(&'}').is_some() => text.push('}'),
+            Some('}') if nested => break,
+            Some('}') => return Err("\"}\" without a matching \"{\"".to_string()),
+            Some('{') => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                if chars.next_if_eq(&'?').is_some() {
+                    segments.push(Segment::Optional(parse_segments(chars, true)?));
+                } else {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(format!("\"{{{placeholder}\" is never closed")),
+                        }
+                    }
+                    segments.push(parse_placeholder(&placeholder)?);
+                }
+            }
+            Some(c) => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Segment, String> {
+    let (name, spec) = placeholder
+        .split_once(':')
+        .map_or((placeholder, None), |(name, spec)| (name, Some(spec)));
+    let field = Field::try_from(name.trim().to_string())?;
+
+    let Some(spec) = spec else {
+        return Ok(Segment::Field {
+            field,
+            width: None,
+            alignment: Alignment::Left,
+        });
+    };
+    let (alignment, width) = if let Some(width) = spec.strip_prefix('>') {
+        (Alignment::Right, width)
+    } else {
+        (Alignment::Left, spec.strip_prefix('<').unwrap_or(spec))
+    };
+    let width = width
+        .parse::<usize>()
+        .ok()
+        .filter(|&width| width > 0)
+        .ok_or_else(|| format!("invalid width \"{spec}\" for \"{name}\""))?;
+
+    Ok(Segment::Field {
+        field,
+        width: Some(width),
+        alignment,
+    })
+}
+
+// Adds the text of the segments to the pieces, telling whether none of their fields were empty
+fn render_segments(
+    segments: &[Segment],
+    value: &impl Fn(Field) -> String,
+    styles: &HashMap<Field, Style>,
+    pieces: &mut Vec<(String, Style)>,
+) -> bool {
+    let mut complete = true;
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => pieces.push((text.clone(), Style::default())),
+            Segment::Field {
+                field,
+                width,
+                alignment,
+            } => {
+                let text = value(*field);
+                complete &= !text.is_empty();
+                let text = match width {
+                    Some(width) => fit(&text, *width, *alignment),
+                    None => text,
+                };
+                pieces.push((text, styles.get(field).copied().unwrap_or_default()));
+            }
+            Segment::Optional(inner) => {
+                let mut inner_pieces = Vec::new();
+                if render_segments(inner, value, styles, &mut inner_pieces) {
+                    pieces.extend(inner_pieces);
+                }
+            }
+        }
+    }
+    complete
+}
+
+// Pads or truncates the text to exactly this many columns
+fn fit(text: &str, width: usize, alignment: Alignment) -> String {
+    let text = text.replace('\n', " ");
+    let text_width = text.width();
+
+    if text_width > width {
+        let mut truncated = String::new();
+        let mut used = 0;
+        for c in text.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if used + char_width + 1 > width {
+                break;
+            }
+            truncated.push(c);
+            used += char_width;
+        }
+        truncated.push('…');
+        return format!("{truncated}{}", " ".repeat(width - used - 1));
+    }
+
+    let padding = " ".repeat(width - text_width);
+    match alignment {
+        Alignment::Left => format!("{text}{padding}"),
+        Alignment::Right => format!("{padding}{text}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    fn parse(template: &str) -> Result<Vec<Segment>, String> {
+        parse_segments(&mut template.chars().peekable(), false)
+    }
+
+    fn field(field: Field) -> Segment {
+        Segment::Field {
+            field,
+            width: None,
+            alignment: Alignment::Left,
+        }
+    }
+
+    fn render(template: &str, value: impl Fn(Field) -> String) -> String {
+        let mut pieces = Vec::new();
+        render_segments(
+            &parse(template).unwrap(),
+            &value,
+            &HashMap::new(),
+            &mut pieces,
+        );
+        pieces.into_iter().map(|(text, _)| text).collect()
+    }
+
+    #[test]
+    fn parses_text_and_fields() {
+        assert_eq!(
+            parse("{date} - {title}").unwrap(),
+            vec![
+                field(Field::Date),
+                Segment::Text(" - ".to_string()),
+                field(Field::Title),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_escaped_braces() {
+        assert_eq!(
+            parse("{{{title}}}").unwrap(),
+            vec![
+                Segment::Text("{".to_string()),
+                field(Field::Title),
+                Segment::Text("}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_nested_optional_sections() {
+        assert_eq!(
+            parse("{?[{feed}{? by {author}}] }").unwrap(),
+            vec![Segment::Optional(vec![
+                Segment::Text("[".to_string()),
+                field(Field::Feed),
+                Segment::Optional(vec![
+                    Segment::Text(" by ".to_string()),
+                    field(Field::Author),
+                ]),
+                Segment::Text("] ".to_string()),
+            ])]
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_braces() {
+        assert!(parse("{title").is_err());
+        assert!(parse("{?{title}").is_err());
+        assert!(parse("title}").is_err());
+    }
+
+    #[test]
+    fn parses_widths_and_alignment() {
+        assert_eq!(
+            parse_placeholder("title:20").unwrap(),
+            Segment::Field {
+                field: Field::Title,
+                width: Some(20),
+                alignment: Alignment::Left,
+            }
+        );
+        assert_eq!(
+            parse_placeholder("date:<8").unwrap(),
+            Segment::Field {
+                field: Field::Date,
+                width: Some(8),
+                alignment: Alignment::Left,
+            }
+        );
+        assert_eq!(
+            parse_placeholder("date:>12").unwrap(),
+            Segment::Field {
+                field: Field::Date,
+                width: Some(12),
+                alignment: Alignment::Right,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_placeholders() {
+        assert!(parse_placeholder("titel").is_err());
+        assert!(parse_placeholder("title:").is_err());
+        assert!(parse_placeholder("title:0").is_err());
+        assert!(parse_placeholder("title:>x").is_err());
+    }
+
+    #[test]
+    fn pads_to_the_width() {
+        assert_eq!(fit("abc", 5, Alignment::Left), "abc  ");
+        assert_eq!(fit("abc", 5, Alignment::Right), "  abc");
+        assert_eq!(fit("abc", 3, Alignment::Left), "abc");
+    }
+
+    #[test]
+    fn truncates_to_the_width() {
+        assert_eq!(fit("abcdef", 4, Alignment::Left), "abc…");
+        assert_eq!(fit("abcdef", 1, Alignment::Right), "…");
+        assert_eq!(fit("a\nb", 3, Alignment::Left), "a b");
+    }
+
+    #[test]
+    fn counts_wide_characters_as_two_columns() {
+        assert_eq!(fit("日本", 5, Alignment::Left), "日本 ");
+        // The second character does not fit next to the ellipsis and is replaced by padding
+        assert_eq!(fit("日本語", 4, Alignment::Left), "日… ");
+    }
+
+    #[test]
+    fn drops_optional_sections_with_empty_fields() {
+        let value = |field| match field {
+            Field::Title => "Title".to_string(),
+            Field::Feed => "Feed".to_string(),
+            _ => String::new(),
+        };
+
+        assert_eq!(render("{?[{feed}] }{title}", value), "[Feed] Title");
+        assert_eq!(render("{title}{? [{author}]}", value), "Title");
+        assert_eq!(
+            render("{?{feed}{? by {author}}: }{title}", value),
+            "Feed: Title"
+        );
+    }
+
+    #[test]
+    fn keeps_empty_fields_outside_optional_sections() {
+        assert_eq!(render("[{author:3}]", |_| String::new()), "[   ]");
+    }
+
+    #[test]
+    fn splits_lines_and_applies_styles() {
+        let template = ListTemplate::try_from("{date}\n  {title}".to_string()).unwrap();
+        let styles = HashMap::from([(Field::Title, Style::new().bold())]);
+        let lines = template.render(|field| format!("{field:?}"), &styles);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].to_string(), "Date");
+        assert_eq!(lines[1].to_string(), "  Title");
+        assert_eq!(lines[1].spans[1].style, Style::new().bold());
+    }
+}
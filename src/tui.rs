@@ -1,133 +1,152 @@
-use crate::config::ListFormat;
+use crate::config::{
+    Icons, PreviewPosition, RendererConfiguration, RendererInput, ScrollingConfiguration,
+};
+use crate::entry::{
+    entry_content_html, entry_date, entry_html, entry_link, entry_summary_html, entry_title,
+    format_date, ListText, NO_CONTENT_PLACEHOLDER, NO_DATE_PLACEHOLDER,
+};
+use crate::fuzzy::fuzzy_match;
+use crate::keymap::{self, Action, Binding};
+use crate::links::{copy_to_clipboard, open_link};
+use crate::render::{html_to_markdown, render_html};
+use crate::renderer::{spawn_renderer, RendererHandle};
+use crate::template::{Field, ListTemplate};
 use crate::{Config, FeedWithCustom};
+use ansi_to_tui::IntoText;
+use chrono::{DateTime, Local, Utc};
+use feed_rs::model::Entry;
 
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::Rect,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Flex, Layout, Rect},
     style::{Style, Stylize},
-    text::Line,
-    widgets::{Block, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, StatefulWidget, Tabs, Widget, Wrap,
+    },
     DefaultTerminal,
 };
-use std::io::Write;
-use std::process::{Command, Stdio};
-use std::{fmt::Debug, io::Cursor};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Full date and time of the entry in the details above articles
+const METADATA_DATE_FORMAT: &str = "%Y-%m-%d %H:%M %:z";
 
 #[derive(Debug)]
-struct AppTheme {
-    accent: Style,
-    text: Style,
-    error: Style,
+pub struct AppTheme {
+    pub accent: Style,
+    pub text: Style,
+    pub error: Style,
 }
 
-#[derive(Debug, Clone)]
-pub struct ScrollUnitHorizontal {
-    pub value: u16,
-    factor: u16,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollDirection {
+    Backward,
+    Forward,
 }
-#[derive(Debug, Clone)]
-pub struct ScrollUnitVertical {
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrollUnit {
     pub value: u16,
+    // Largest offset that still fills the view, set on every draw
+    max: u16,
+    // Lines or columns moved by a single step
+    lines: u16,
+    // Multiplies the step for every quickly repeated step in the same direction
     factor: u16,
+    last_step: Option<(ScrollDirection, Instant)>,
+    repeats: u32,
 }
 
-pub enum ScrollDirectionVertical {
-    Up,
-    Down,
-}
-pub enum ScrollDirectionHorizontal {
-    Left,
-    Right,
-}
+impl ScrollUnit {
+    const REPEAT_WINDOW: Duration = Duration::from_millis(150);
+    const MAX_REPEATS: u32 = 4;
 
-impl ScrollableUnit<ScrollDirectionVertical> for ScrollUnitVertical {
-    fn scroll(&mut self, value: u16, direction: ScrollDirectionVertical) -> Result<(), String> {
-        match direction {
-            ScrollDirectionVertical::Up => {
-                self.value = self
-                    .value
-                    .checked_sub(value)
-                    .ok_or("Failed scrolling, whatever")?
-                    * self.factor;
-                Ok(())
-            }
-            ScrollDirectionVertical::Down => {
-                self.value = self
-                    .value
-                    .checked_add(value)
-                    .ok_or("Failed scrolling, whatever")?
-                    * self.factor;
-                Ok(())
-            }
+    fn new(lines: u16, factor: u16) -> Self {
+        Self {
+            lines: lines.max(1),
+            factor: factor.max(1),
+            ..Default::default()
         }
     }
-    fn reset(&mut self) {
-        self.value = 0;
+
+    // Moves by the configured step, accelerating while the key is held down
+    fn step(&mut self, direction: ScrollDirection) {
+        let now = Instant::now();
+        let repeated = self.last_step.is_some_and(|(last_direction, at)| {
+            last_direction == direction && now.duration_since(at) <= Self::REPEAT_WINDOW
+        });
+        self.repeats = if repeated {
+            (self.repeats + 1).min(Self::MAX_REPEATS)
+        } else {
+            0
+        };
+        self.last_step = Some((direction, now));
+
+        let amount = self
+            .lines
+            .saturating_mul(self.factor.saturating_pow(self.repeats));
+        self.scroll_by(amount, direction);
     }
-}
 
-impl ScrollableUnit<ScrollDirectionHorizontal> for ScrollUnitHorizontal {
-    fn scroll(&mut self, value: u16, direction: ScrollDirectionHorizontal) -> Result<(), String> {
-        match direction {
-            ScrollDirectionHorizontal::Left => {
-                self.value = self
-                    .value
-                    .checked_sub(value)
-                    .ok_or("Failed scrolling, whatever")?
-                    * self.factor;
-                Ok(())
-            }
-            ScrollDirectionHorizontal::Right => {
-                self.value = self
-                    .value
-                    .checked_add(value)
-                    .ok_or("Failed scrolling, whatever")?
-                    * self.factor;
-                Ok(())
-            }
-        }
+    fn scroll_by(&mut self, amount: u16, direction: ScrollDirection) {
+        self.value = match direction {
+            ScrollDirection::Backward => self.value.saturating_sub(amount),
+            ScrollDirection::Forward => self.value.saturating_add(amount).min(self.max),
+        };
+    }
+
+    fn set_max(&mut self, max: u16) {
+        self.max = max;
+        self.value = self.value.min(max);
     }
+
+    fn scroll_to_end(&mut self) {
+        self.value = self.max;
+    }
+
     fn reset(&mut self) {
         self.value = 0;
+        self.last_step = None;
     }
 }
 
-impl Default for ScrollUnitVertical {
-    fn default() -> Self {
-        Self {
-            value: 0,
-            factor: 1,
-        }
-    }
+#[derive(Debug, Clone, Default)]
+pub struct ScrollState {
+    pub vertical: ScrollUnit,
+    pub horizontal: ScrollUnit,
+    // Height of the view, moved by page up and down
+    page: u16,
 }
-impl Default for ScrollUnitHorizontal {
-    fn default() -> Self {
+
+impl ScrollState {
+    fn new(config: &ScrollingConfiguration) -> Self {
         Self {
-            value: 0,
-            factor: 1,
+            vertical: ScrollUnit::new(config.y_lines, config.y_factor),
+            horizontal: ScrollUnit::new(config.x_lines, config.x_factor),
+            page: 0,
         }
     }
-}
-
-#[derive(Debug, Clone)]
-pub struct ScrollState(ScrollUnitVertical, ScrollUnitHorizontal);
 
-pub trait ScrollableUnit<Direction> {
-    fn scroll(&mut self, value: u16, direction: Direction) -> Result<(), String>;
-    fn reset(&mut self);
-}
+    // Keeps the offsets within the content, given its size and the size of the view
+    fn set_bounds(&mut self, content: (u16, u16), view: Rect) {
+        self.vertical.set_max(content.0.saturating_sub(view.height));
+        self.horizontal
+            .set_max(content.1.saturating_sub(view.width));
+        self.page = view.height.saturating_sub(1).max(1);
+    }
 
-impl From<ScrollState> for (u16, u16) {
-    fn from(value: ScrollState) -> Self {
-        (value.0.value, value.1.value)
+    fn page(&mut self, direction: ScrollDirection) {
+        self.vertical.scroll_by(self.page, direction);
     }
-}
 
-impl ScrollState {
     fn reset(&mut self) {
-        self.0.reset();
-        self.1.reset();
+        self.vertical.reset();
+        self.horizontal.reset();
     }
 }
 
@@ -135,20 +154,88 @@ impl ScrollState {
 pub struct App {
     config: Config,
     theme: AppTheme,
+    // Layout of the entries in lists, from the configured template or list format
+    list_template: ListTemplate,
+    list_styles: HashMap<Field, Style>,
+    icons: Icons,
     screen: CurrentScreen,
     selected_feed_idx: usize,
-    feeds: Vec<Option<FeedWithCustom>>,
+    // Group names along with the indexes of their feeds, in configuration order
+    groups: Vec<(String, Vec<usize>)>,
+    selected_group_idx: usize,
+    feeds: Vec<FeedWithCustom>,
     exit: bool,
     scroll_number: ScrollState,
+    // (vertical, horizontal) offsets of articles read this session, by entry id
+    scroll_positions: HashMap<String, (u16, u16)>,
+    // Offsets to go back to once the open article is done rendering
+    restore_scroll: Option<(u16, u16)>,
     list_state: ListState,
     selected_entry: Option<feed_rs::model::Entry>,
-    buffered_render: Option<String>,
+    buffered_render: Option<Text<'static>>,
+    // (feed index, entry index) pairs for every entry across all feeds, newest first
+    timeline: Vec<(usize, usize)>,
+    timeline_state: ListState,
+    article_origin: CurrentScreen,
+    // Feed the open article belongs to, and its index in the feed's entries
+    article_feed_idx: usize,
+    article_entry_idx: usize,
+    // Width the open article was rendered at, it is rendered again when the pane changes size
+    article_width: u16,
+    focus: FocusedPane,
+    feed_list_state: ListState,
+    // Show the summary of the article instead of its full content
+    show_summary: bool,
+    // Show authors, dates, categories and links above the article
+    show_metadata: bool,
+    pending_render: Option<PendingRender>,
+    // Links found in the open article, in the order they are numbered
+    article_links: Vec<String>,
+    link_picker: Option<ListState>,
+    show_help: bool,
+    switcher: Option<QuickSwitcher>,
+    // Feedback for the last action, shown in the status line for a while
+    status_message: Option<StatusMessage>,
+    // When the feeds were fetched
+    fetched_at: DateTime<Local>,
+    // (entry id, width, rendered text) of the last previewed entry
+    preview_cache: Option<(String, u16, Text<'static>)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FocusedPane {
+    Feeds,
+    Entries,
+}
+
+// External renderer still running for the open article
+#[derive(Debug)]
+struct PendingRender {
+    // Replacing or dropping it kills the renderer
+    handle: RendererHandle,
+    // Built-in rendering, shown if the renderer fails
+    fallback: Text<'static>,
+}
+
+// Overlay finding a feed by name
+#[derive(Debug)]
+struct QuickSwitcher {
+    query: String,
+    list_state: ListState,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
+struct StatusMessage {
+    text: String,
+    is_error: bool,
+    shown_at: Instant,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum CurrentScreen {
     Selection,
     SingleArticle,
+    Timeline,
 }
 
 impl Widget for &mut App {
@@ -156,35 +243,100 @@ impl Widget for &mut App {
     where
         Self: Sized,
     {
+        let [area, status_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+        self.render_status(status_area, buf);
+
         match self.screen {
             CurrentScreen::Selection => self.render_list(area, buf),
             CurrentScreen::SingleArticle => self.render_article(area, buf),
+            CurrentScreen::Timeline => self.render_timeline(area, buf),
+        }
+
+        if self.link_picker.is_some() {
+            self.render_link_picker(area, buf);
+        }
+        if self.switcher.is_some() {
+            self.render_switcher(area, buf);
+        }
+        if self.show_help {
+            self.render_help(area, buf);
         }
     }
 }
 
 impl App {
     const LARGE_NUMBER: usize = 5000;
+    const TICK_RATE: Duration = Duration::from_millis(100);
+    const STATUS_DURATION: Duration = Duration::from_secs(4);
+
+    pub fn new(feeds: Vec<FeedWithCustom>, config: Config, fetched_at: DateTime<Local>) -> Self {
+        let mut timeline: Vec<(usize, usize)> = feeds
+            .iter()
+            .enumerate()
+            .flat_map(|(feed_idx, feed)| {
+                (0..feed.filtered_entries.len()).map(move |entry_idx| (feed_idx, entry_idx))
+            })
+            .collect();
+
+        timeline.sort_by_key(|(feed_idx, entry_idx)| {
+            let entry = &feeds[*feed_idx].filtered_entries[*entry_idx];
+            std::cmp::Reverse(entry_date(entry))
+        });
+
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        for (feed_idx, feed) in feeds.iter().enumerate() {
+            let group_name = feed
+                .group
+                .clone()
+                .unwrap_or_else(|| "Ungrouped".to_string());
+            match groups.iter_mut().find(|(name, _)| *name == group_name) {
+                Some((_, members)) => members.push(feed_idx),
+                None => groups.push((group_name, vec![feed_idx])),
+            }
+        }
 
-    pub fn new(feeds: Vec<Option<FeedWithCustom>>, config: Config) -> Self {
         Self {
             feeds,
+            timeline,
+            groups,
+            selected_group_idx: 0,
+            timeline_state: ListState::default(),
+            article_origin: CurrentScreen::Selection,
+            article_feed_idx: 0,
+            article_entry_idx: 0,
+            article_width: 0,
+            focus: FocusedPane::Entries,
+            feed_list_state: ListState::default(),
+            show_summary: false,
+            show_metadata: true,
+            preview_cache: None,
+            status_message: None,
+            fetched_at,
+            pending_render: None,
+            article_links: Vec::new(),
+            link_picker: None,
+            show_help: false,
+            switcher: None,
             exit: false,
             screen: CurrentScreen::Selection,
             selected_feed_idx: 0,
             list_state: ListState::default(),
             selected_entry: None,
-            scroll_number: ScrollState(
-                ScrollUnitVertical {
-                    value: config.scrolling.y_lines,
-                    factor: config.scrolling.y_factor,
-                },
-                ScrollUnitHorizontal {
-                    value: config.scrolling.x_lines,
-                    factor: config.scrolling.x_factor,
-                },
-            ),
+            scroll_number: ScrollState::new(&config.scrolling),
+            scroll_positions: HashMap::new(),
+            restore_scroll: None,
             buffered_render: None,
+            list_template: config
+                .list_template
+                .clone()
+                .unwrap_or_else(|| config.list_format.template()),
+            icons: config.icons.icons(config.nerd_fonts),
+            list_styles: config
+                .list_styles
+                .iter()
+                .map(|(field, style)| (*field, Style::from(style)))
+                .collect(),
             theme: AppTheme {
                 error: Style::new().fg(ratatui::style::Color::Rgb(
                     config.theme.error.red,
@@ -206,293 +358,1538 @@ impl App {
         }
     }
 
-    fn render_article(&self, area: Rect, buf: &mut Buffer) {
-        let init_block = Block::bordered()
-            .border_type(ratatui::widgets::BorderType::Rounded)
-            .title_position(ratatui::widgets::block::Position::Top)
-            .title_alignment(ratatui::layout::Alignment::Center)
-            .style(self.theme.accent)
-            .title_top(
-                Line::from(format!(
-                    "{} Esc",
-                    if self.config.nerd_fonts { "" } else { "<" }
-                ))
-                .left_aligned(),
-            )
-            .title(
-                Line::from(self.selected_entry.clone().unwrap().title.unwrap().content)
-                    .style(self.theme.text),
-            )
-            .title_bottom(format!(
-                "Use {} to move, r to reset position",
-                if self.config.nerd_fonts {
-                    "   "
-                } else {
-                    "↑ ↓ < >"
-                }
+    // Message of the last action or where we are, with the state of the feeds on the right
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let failed = self.feeds.iter().filter(|feed| feed.feed.is_err()).count();
+        let mut right = Vec::new();
+        if failed > 0 {
+            right.push(Span::styled(
+                format!(
+                    "{failed} feed{} failed to load  ",
+                    if failed == 1 { "" } else { "s" }
+                ),
+                self.theme.error,
             ));
+        }
+        right.push(Span::styled(
+            format!("Fetched {} ", self.fetched_at.format("%H:%M")),
+            self.theme.accent,
+        ));
 
-        Paragraph::new(
-            self.buffered_render
-                .clone()
-                .unwrap_or_else(|| "Failed rendering article".to_string()),
-        )
-        .alignment(self.config.alignment.article.clone().into())
-        .scroll(self.scroll_number.clone().into())
-        .wrap(Wrap { trim: true })
-        .style(self.theme.text)
-        .block(init_block)
-        .render(area, buf);
+        let left = match &self.status_message {
+            Some(message) => Span::styled(
+                format!(" {}", message.text),
+                if message.is_error {
+                    self.theme.error
+                } else {
+                    self.theme.text
+                },
+            ),
+            None => Span::styled(format!(" {}", self.status_position()), self.theme.accent),
+        };
+
+        Line::from(left).render(area, buf);
+        Line::from(right).right_aligned().render(area, buf);
     }
 
-    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let mut base_block = Block::bordered()
+    fn status_position(&self) -> String {
+        match self.screen {
+            CurrentScreen::Selection => {
+                let feed = &self.feeds[self.selected_feed_idx];
+                format!(
+                    "Feed {}/{} {} {} entries",
+                    self.group_position() + 1,
+                    self.group_feeds().len(),
+                    self.icons.separator,
+                    feed.filtered_entries.len()
+                )
+            }
+            CurrentScreen::Timeline => format!(
+                "{} entries from {} feeds",
+                self.timeline.len(),
+                self.feeds.len()
+            ),
+            CurrentScreen::SingleArticle => {
+                let feed = &self.feeds[self.article_feed_idx];
+                let (position, count) = match self.article_origin {
+                    CurrentScreen::Timeline => (
+                        self.timeline_state.selected().unwrap_or(0),
+                        self.timeline.len(),
+                    ),
+                    _ => (self.article_entry_idx, feed.filtered_entries.len()),
+                };
+                format!(
+                    "Entry {}/{count} {} {}",
+                    position + 1,
+                    self.icons.separator,
+                    feed.display_name()
+                )
+            }
+        }
+    }
+
+    fn render_article(&mut self, area: Rect, buf: &mut Buffer) {
+        let mut init_block = Block::bordered()
             .border_type(ratatui::widgets::BorderType::Rounded)
-            .title_top(
-                Line::from(format!(
-                    "{} Esc",
-                    if self.config.nerd_fonts { "" } else { "x" }
-                ))
-                .left_aligned(),
-            )
             .title_position(ratatui::widgets::block::Position::Top)
-            .title_alignment(ratatui::layout::Alignment::Center);
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title_top(Line::from(format!("{} Esc", self.icons.back)).left_aligned())
+            .title(
+                Line::from(
+                    self.selected_entry
+                        .as_ref()
+                        .map_or_else(String::new, entry_title),
+                )
+                .style(self.theme.text),
+            );
+        let hint = Line::from(format!(
+            "Use {} to move, {}",
+            self.move_hint(true),
+            keymap::footer(keymap::ARTICLE, &self.icons)
+        ));
 
-        if self.feeds.get(self.selected_feed_idx + 1).is_some() {
-            base_block = base_block.title_top(
+        if let Some(binding) = keymap::binding_for(keymap::ARTICLE, Action::ToggleSummary)
+            .filter(|_| self.selected_entry.as_ref().is_some_and(has_summary_toggle))
+        {
+            init_block = init_block.title_top(
                 Line::from(format!(
-                    "Next {}",
-                    if self.config.nerd_fonts { "" } else { ">" }
+                    "{} for {}",
+                    keymap::keys_label(binding, &self.icons),
+                    if self.show_summary {
+                        "content"
+                    } else {
+                        "summary"
+                    }
                 ))
                 .right_aligned(),
             );
         }
-        if self.selected_feed_idx.checked_sub(1).is_some()
-            && self.feeds.get(self.selected_feed_idx - 1).is_some()
+
+        let metadata = match &self.selected_entry {
+            Some(entry) if self.show_metadata => self.metadata_lines(entry),
+            _ => Vec::new(),
+        };
+        let [metadata_area, body_area] = Layout::vertical([
+            Constraint::Length(if metadata.is_empty() {
+                0
+            } else {
+                u16::try_from(metadata.len()).unwrap_or(u16::MAX) + 1
+            }),
+            Constraint::Fill(1),
+        ])
+        .areas(init_block.inner(area));
+
+        if body_area.width != self.article_width {
+            self.article_width = body_area.width;
+            // A renderer shows a placeholder meanwhile, which would clamp the offsets back to the top
+            if self.restore_scroll.is_none() {
+                self.restore_scroll = Some((
+                    self.scroll_number.vertical.value,
+                    self.scroll_number.horizontal.value,
+                ));
+            }
+            self.render_selected_entry();
+        }
+
+        let (content_height, content_width) =
+            self.buffered_render.as_ref().map_or((0, 0), |text| {
+                (
+                    u16::try_from(text.lines.len()).unwrap_or(u16::MAX),
+                    u16::try_from(text.width()).unwrap_or(u16::MAX),
+                )
+            });
+        // Only once rendered, the placeholder shown meanwhile would clamp the offsets to nothing
+        if self.pending_render.is_none() {
+            if let Some((vertical, horizontal)) = self.restore_scroll.take() {
+                self.scroll_number.vertical.value = vertical;
+                self.scroll_number.horizontal.value = horizontal;
+            }
+        }
+        self.scroll_number
+            .set_bounds((content_height, content_width), body_area);
+        let y_offset = self.scroll_number.vertical.value;
+        let x_offset = self.scroll_number.horizontal.value;
+
+        let position = (content_height > 0).then(|| {
+            Line::from(format!(
+                " line {}/{content_height} ",
+                (y_offset + 1).min(content_height)
+            ))
+            .right_aligned()
+        });
+        // The centered hint is shortened to the global keys, then dropped, when both do not fit
+        let position_width = position.as_ref().map_or(0, Line::width);
+        if let Some(hint) = [hint, Line::from(keymap::footer(&[], &self.icons))]
+            .into_iter()
+            .find(|hint| {
+                (usize::from(area.width).saturating_sub(hint.width())) / 2 > position_width + 1
+            })
         {
-            base_block = base_block.title_top(
-                Line::from(format!(
-                    "{} Prev",
-                    if self.config.nerd_fonts { "" } else { "<" }
-                ))
-                .left_aligned(),
-            );
+            init_block = init_block.title_bottom(hint);
+        }
+        if let Some(position) = position {
+            init_block = init_block.title_bottom(position);
+        }
+        init_block.render(area, buf);
+
+        if !metadata.is_empty() {
+            Paragraph::new(metadata)
+                .block(
+                    Block::new()
+                        .borders(Borders::BOTTOM)
+                        .style(self.theme.accent),
+                )
+                .render(metadata_area, buf);
         }
 
-        if let Some(current_feed) = self.feeds[self.selected_feed_idx].clone() {
-            let loaded_rss_block = base_block
+        let visible_lines: Vec<Line> = self.buffered_render.as_ref().map_or_else(
+            || vec![Line::raw("Failed rendering article")],
+            |text| {
+                text.lines
+                    .iter()
+                    .skip(y_offset.into())
+                    .take(body_area.height.into())
+                    .map(|line| {
+                        // Only lines that do not fit, like preformatted ones, scroll sideways
+                        if line.width() > body_area.width.into() {
+                            skip_columns(line, x_offset.into())
+                        } else {
+                            line.clone()
+                        }
+                    })
+                    .collect()
+            },
+        );
+
+        Paragraph::new(visible_lines)
+            .alignment(self.config.alignment.article.clone().into())
+            .style(self.theme.text)
+            .render(body_area, buf);
+
+        // Drawn over the right border, next to the body
+        if content_height > body_area.height {
+            let scrollbar_area = Rect::new(area.x, body_area.y, area.width, body_area.height);
+            let mut scrollbar_state =
+                ScrollbarState::new(usize::from(content_height - body_area.height) + 1)
+                    .viewport_content_length(body_area.height.into())
+                    .position(y_offset.into());
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
                 .style(self.theme.accent)
-                .title(
-                    Line::from(if let Some(custom_title) = current_feed.name {
-                        custom_title
-                    } else {
-                        current_feed.feed.clone().title.unwrap().content
+                .render(scrollbar_area, buf, &mut scrollbar_state);
+        }
+    }
+
+    fn metadata_lines(&self, entry: &Entry) -> Vec<Line<'static>> {
+        let mut fields: Vec<(&str, String)> = Vec::new();
+
+        if !entry.authors.is_empty() {
+            fields.push((
+                "Authors",
+                entry
+                    .authors
+                    .iter()
+                    .map(|author| author.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+        if let Some(published) = entry.published {
+            fields.push((
+                "Published",
+                published
+                    .with_timezone(&Local)
+                    .format(METADATA_DATE_FORMAT)
+                    .to_string(),
+            ));
+        }
+        if let Some(updated) = entry.updated {
+            fields.push((
+                "Updated",
+                updated
+                    .with_timezone(&Local)
+                    .format(METADATA_DATE_FORMAT)
+                    .to_string(),
+            ));
+        }
+        if !entry.categories.is_empty() {
+            fields.push((
+                "Categories",
+                entry
+                    .categories
+                    .iter()
+                    .map(|category| {
+                        category
+                            .label
+                            .clone()
+                            .unwrap_or_else(|| category.term.clone())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+        if let Some(link) = entry_link(entry) {
+            fields.push(("Link", link.to_string()));
+        }
+        for enclosure in entry
+            .links
+            .iter()
+            .filter(|link| link.rel.as_deref() == Some("enclosure"))
+        {
+            fields.push((
+                "Enclosure",
+                media_description(
+                    &enclosure.href,
+                    enclosure.media_type.clone(),
+                    enclosure.length,
+                ),
+            ));
+        }
+        for media in entry.media.iter().flat_map(|media| &media.content) {
+            let Some(url) = &media.url else {
+                continue;
+            };
+            fields.push((
+                "Media",
+                media_description(
+                    url.as_str(),
+                    media.content_type.as_ref().map(ToString::to_string),
+                    media.size,
+                ),
+            ));
+        }
+
+        fields
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label}: "), self.theme.accent.bold()),
+                    Span::styled(value, self.theme.text),
+                ])
+            })
+            .collect()
+    }
+
+    fn entry_list_item(
+        &self,
+        e: &Entry,
+        text: &ListText,
+        feed_name: Option<&str>,
+        is_new: bool,
+    ) -> ListItem<'static> {
+        let format_optional_date = |date: Option<DateTime<Utc>>| {
+            date.map_or_else(String::new, |date| {
+                format_date(
+                    date,
+                    &self.config.date_format,
+                    self.config.show_time,
+                    Local::now(),
+                )
+            })
+        };
+
+        let lines = self.list_template.render(
+            |field| match field {
+                Field::New if is_new => self.icons.new.clone(),
+                Field::New => String::new(),
+                Field::DateIcon => self.icons.date.clone(),
+                Field::TitleIcon => self.icons.title.clone(),
+                Field::Date => entry_date(e).map_or_else(
+                    || NO_DATE_PLACEHOLDER.to_string(),
+                    |date| format_optional_date(Some(date)),
+                ),
+                Field::Published => format_optional_date(e.published),
+                Field::Updated => format_optional_date(e.updated),
+                Field::Title => text.title.clone(),
+                Field::Feed => feed_name.unwrap_or_default().to_string(),
+                Field::Author => e
+                    .authors
+                    .iter()
+                    .map(|author| author.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Field::Categories => e
+                    .categories
+                    .iter()
+                    .map(|category| {
+                        category
+                            .label
+                            .clone()
+                            .unwrap_or_else(|| category.term.clone())
                     })
-                    .style(self.theme.text),
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Field::Summary => text.summary.clone().unwrap_or_default(),
+                Field::Link => entry_link(e).unwrap_or_default().to_string(),
+            },
+            &self.list_styles,
+        );
+        let item = ListItem::from(Text::from(lines));
+
+        // Stands out from the accent color of the rest of the list
+        if is_new {
+            item.style(self.theme.text)
+        } else {
+            item
+        }
+    }
+
+    fn render_timeline(&mut self, area: Rect, buf: &mut Buffer) {
+        let timeline_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_top(Line::from(format!("{} Esc", self.icons.back)).left_aligned())
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title(Line::from("Timeline").style(self.theme.text))
+            .title_bottom(format!(
+                "Use {} to move, {}",
+                self.move_hint(false),
+                keymap::footer(keymap::TIMELINE, &self.icons)
+            ));
+
+        let raw_list: Vec<ListItem> = self
+            .timeline
+            .iter()
+            .map(|(feed_idx, entry_idx)| {
+                let feed = &self.feeds[*feed_idx];
+                let entry = &feed.filtered_entries[*entry_idx];
+                self.entry_list_item(
+                    entry,
+                    &feed.list_texts[*entry_idx],
+                    Some(&feed.display_name()),
+                    feed.is_new(entry),
                 )
-                .title_bottom(format!(
-                    "Use {} to move, r to reset selection, <Enter> to select",
-                    if self.config.nerd_fonts {
-                        " "
-                    } else {
-                        "↑ ↓"
-                    }
-                ));
+            })
+            .collect();
 
-            let raw_list: Vec<ListItem> = current_feed
-                .filtered_entries
-                .iter()
-                .map(|e| {
-                    ListItem::from(format!(
-                        "{} {} {}{} {} {}",
-                        if self.config.nerd_fonts {
-                            "󰃭"
-                        } else {
-                            "📅"
-                        },
-                        e.updated.unwrap().date_naive(),
-                        match self.config.list_format {
-                            ListFormat::Compact => "",
-                            ListFormat::Extended => "\n",
-                        },
-                        if self.config.nerd_fonts {
-                            "󰦨"
+        let (list_area, preview_area) = self.split_preview(area);
+
+        StatefulWidget::render(
+            entry_list(&self.theme, &self.icons, raw_list, timeline_block),
+            list_area,
+            buf,
+            &mut self.timeline_state,
+        );
+
+        if let Some(preview_area) = preview_area {
+            let entry = self.highlighted_entry().cloned();
+            self.render_preview(entry.as_ref(), preview_area, buf);
+        }
+    }
+
+    // Every binding of the open overlay or the current screen, straight from the tables the key handlers use
+    fn render_help(&self, area: Rect, buf: &mut Buffer) {
+        let (screen_name, bindings) = if self.switcher.is_some() {
+            ("Go to feed", keymap::SWITCHER)
+        } else if self.link_picker.is_some() {
+            ("Links", keymap::LINK_PICKER)
+        } else {
+            match self.screen {
+                CurrentScreen::Selection => ("Feeds", keymap::SELECTION),
+                CurrentScreen::SingleArticle => ("Article", keymap::ARTICLE),
+                CurrentScreen::Timeline => ("Timeline", keymap::TIMELINE),
+            }
+        };
+        let bindings: Vec<&Binding> = bindings
+            .iter()
+            .filter(|binding| binding.action != Action::SwitchPane || self.config.layout.sidebar)
+            .collect();
+        // Overlays take over every key, the global ones included
+        let global: &[Binding] = if self.switcher.is_some() || self.link_picker.is_some() {
+            &[]
+        } else {
+            keymap::GLOBAL
+        };
+
+        let key_width = bindings
+            .iter()
+            .copied()
+            .chain(global)
+            .map(|binding| keymap::keys_label(binding, &self.icons).chars().count())
+            .max()
+            .unwrap_or(0);
+        let binding_line = |binding: &Binding| {
+            let mut description = binding.description.chars();
+            let description = description
+                .next()
+                .map(|first| first.to_uppercase().chain(description).collect::<String>())
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        " {:<key_width$}  ",
+                        keymap::keys_label(binding, &self.icons)
+                    ),
+                    self.theme.accent,
+                ),
+                Span::styled(description, self.theme.text),
+            ])
+        };
+
+        let mut lines = vec![Line::from(screen_name).style(self.theme.accent.bold())];
+        lines.extend(bindings.into_iter().map(binding_line));
+        if !global.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from("Everywhere").style(self.theme.accent.bold()));
+            lines.extend(global.iter().map(binding_line));
+        }
+
+        let [help_area] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [help_area] = Layout::vertical([Constraint::Length(
+            u16::try_from(lines.len()).unwrap_or(u16::MAX) + 2,
+        )])
+        .flex(Flex::Center)
+        .areas(help_area);
+
+        let help_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title(Line::from("Help").style(self.theme.text))
+            .title_bottom(keymap::overlay_footer(keymap::HELP, &self.icons));
+
+        Clear.render(help_area, buf);
+        Paragraph::new(lines)
+            .block(help_block)
+            .render(help_area, buf);
+    }
+
+    fn render_switcher(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(switcher) = &self.switcher else {
+            return;
+        };
+        let matches = self.switcher_matches(&switcher.query);
+
+        let [switcher_area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [switcher_area] = Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(switcher_area);
+
+        let mut switcher_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title(Line::from("Go to feed").style(self.theme.text));
+        // Shortened to the keys, then dropped, when it does not fit
+        let footer = keymap::overlay_footer(keymap::SWITCHER, &self.icons);
+        if let Some(hint) = [format!("Type to filter, {footer}"), footer]
+            .into_iter()
+            .find(|hint| hint.width() + 2 <= usize::from(switcher_area.width))
+        {
+            switcher_block = switcher_block.title_bottom(hint);
+        }
+
+        let inner_area = switcher_block.inner(switcher_area);
+        Clear.render(switcher_area, buf);
+        switcher_block.render(switcher_area, buf);
+
+        let [query_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner_area);
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", self.theme.accent),
+            Span::styled(format!("{}_", switcher.query), self.theme.text),
+        ]))
+        .block(Block::new().borders(Borders::BOTTOM))
+        .render(query_area, buf);
+
+        let raw_list: Vec<ListItem> = matches
+            .iter()
+            .map(|(feed_idx, positions)| {
+                let spans: Vec<Span> = switcher_label(&self.feeds[*feed_idx])
+                    .chars()
+                    .enumerate()
+                    .map(|(idx, c)| {
+                        if positions.contains(&idx) {
+                            Span::styled(c.to_string(), self.theme.accent.bold())
                         } else {
-                            "📜"
-                        },
-                        e.title.clone().unwrap().content,
-                        match self.config.list_format {
-                            ListFormat::Compact => "",
-                            ListFormat::Extended => "\n\n",
+                            Span::styled(c.to_string(), self.theme.text)
                         }
-                    ))
-                })
-                .collect();
+                    })
+                    .collect();
+                ListItem::from(Line::from(spans))
+            })
+            .collect();
 
-            let rss_list = List::new(raw_list)
-                .highlight_symbol(if self.config.nerd_fonts { "❯" } else { ">" })
-                .style(self.theme.accent)
-                .highlight_spacing(ratatui::widgets::HighlightSpacing::Always)
-                .highlight_style(self.theme.text.bold())
-                .direction(ratatui::widgets::ListDirection::TopToBottom)
-                .block(loaded_rss_block);
+        let list = entry_list(&self.theme, &self.icons, raw_list, Block::new());
+        if let Some(switcher) = &mut self.switcher {
+            StatefulWidget::render(list, list_area, buf, &mut switcher.list_state);
+        }
+    }
+
+    fn render_link_picker(&mut self, area: Rect, buf: &mut Buffer) {
+        let [picker_area] = Layout::horizontal([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [picker_area] = Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(picker_area);
+
+        let picker_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title(Line::from("Links").style(self.theme.text))
+            .title_bottom(keymap::overlay_footer(keymap::LINK_PICKER, &self.icons));
+
+        let raw_list: Vec<ListItem> = self
+            .article_links
+            .iter()
+            .enumerate()
+            .map(|(idx, link)| ListItem::from(format!("[{}] {link}", idx + 1)))
+            .collect();
+
+        Clear.render(picker_area, buf);
+        let list = entry_list(&self.theme, &self.icons, raw_list, picker_block);
+        if let Some(picker_state) = &mut self.link_picker {
+            StatefulWidget::render(list, picker_area, buf, picker_state);
+        }
+    }
+
+    fn split_preview(&self, area: Rect) -> (Rect, Option<Rect>) {
+        let layout = match self.config.layout.preview {
+            PreviewPosition::None => return (area, None),
+            PreviewPosition::Bottom => Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]),
+            PreviewPosition::Right => {
+                Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)])
+            }
+        };
+        let [list_area, preview_area] = layout.areas(area);
+        (list_area, Some(preview_area))
+    }
+
+    fn render_preview(&mut self, entry: Option<&Entry>, area: Rect, buf: &mut Buffer) {
+        let preview_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.theme.accent)
+            .title(Line::from("Preview").style(self.theme.text));
+
+        let width = preview_block.inner(area).width;
 
-            StatefulWidget::render(rss_list, area, buf, &mut self.list_state);
+        let preview = match entry {
+            Some(entry) => {
+                let is_cached = self
+                    .preview_cache
+                    .as_ref()
+                    .is_some_and(|(id, cached_width, _)| *id == entry.id && *cached_width == width);
+
+                if !is_cached {
+                    let rendered = entry_html(entry).map_or_else(
+                        || Text::raw(NO_CONTENT_PLACEHOLDER),
+                        |html| {
+                            render_html(&html, width.into(), &self.theme).map_or_else(
+                                |_| Text::raw("Failed rendering article"),
+                                |rendered| rendered.text,
+                            )
+                        },
+                    );
+                    self.preview_cache = Some((entry.id.clone(), width, rendered));
+                }
+
+                self.preview_cache.as_ref().unwrap().2.clone()
+            }
+            None => Text::default(),
+        };
+
+        Paragraph::new(preview)
+            .style(self.theme.text)
+            .block(preview_block)
+            .render(area, buf);
+    }
+
+    // Keys moving around, for the "Use ... to move" hints
+    fn move_hint(&self, horizontal: bool) -> String {
+        if horizontal {
+            format!(
+                "{} {} {} {}",
+                self.icons.up, self.icons.down, self.icons.prev, self.icons.next
+            )
+        } else {
+            format!("{} {}", self.icons.up, self.icons.down)
+        }
+    }
+
+    fn pane_style(&self, pane: FocusedPane) -> Style {
+        if self.config.layout.sidebar && self.focus != pane {
+            self.theme.accent.dim()
+        } else {
+            self.theme.accent
+        }
+    }
+
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let area = if self.groups.len() > 1 {
+            let [tabs_area, list_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+
+            Tabs::new(self.groups.iter().map(|(name, members)| {
+                let new_count = members
+                    .iter()
+                    .map(|&feed_idx| self.feeds[feed_idx].new_count())
+                    .sum();
+                format!(
+                    "{name}{}",
+                    new_count_label(new_count, &format!(" {} ", self.icons.separator))
+                )
+            }))
+            .select(self.selected_group_idx)
+            .style(self.theme.accent)
+            .highlight_style(self.theme.text.bold())
+            .render(tabs_area, buf);
+
+            list_area
         } else {
-            let failure_block = base_block
-                .title(Line::from("Error").centered().style(self.theme.text))
-                .style(self.theme.error);
+            area
+        };
+
+        if self.config.layout.sidebar {
+            let [sidebar_area, entries_area] =
+                Layout::horizontal([Constraint::Percentage(30), Constraint::Fill(1)]).areas(area);
+            self.render_sidebar(sidebar_area, buf);
+            self.render_entries(entries_area, buf);
+        } else {
+            self.render_entries(area, buf);
+        }
+    }
+
+    fn render_sidebar(&mut self, area: Rect, buf: &mut Buffer) {
+        let mut sidebar_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .style(self.pane_style(FocusedPane::Feeds))
+            .title(Line::from("Feeds").style(self.theme.text));
+        if let Some(binding) = keymap::binding_for(keymap::SELECTION, Action::SwitchPane) {
+            sidebar_block = sidebar_block.title_bottom(keymap::hint(binding, &self.icons));
+        }
+
+        let raw_list: Vec<ListItem> = self
+            .group_feeds()
+            .iter()
+            .map(|&feed_idx| {
+                let feed = &self.feeds[feed_idx];
+                if feed.feed.is_ok() {
+                    ListItem::from(format!(
+                        "{} ({}{})",
+                        feed.display_name(),
+                        feed.filtered_entries.len(),
+                        new_count_label(feed.new_count(), ", ")
+                    ))
+                } else {
+                    ListItem::from(format!("{} {}", self.icons.error, feed.display_name()))
+                        .style(self.theme.error)
+                }
+            })
+            .collect();
+
+        self.feed_list_state.select(Some(self.group_position()));
 
-            Paragraph::new(Line::from("Failed fetching RSS information"))
+        StatefulWidget::render(
+            entry_list(&self.theme, &self.icons, raw_list, sidebar_block),
+            area,
+            buf,
+            &mut self.feed_list_state,
+        );
+    }
+
+    fn render_entries(&mut self, area: Rect, buf: &mut Buffer) {
+        let mut base_block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_top(Line::from(format!("{} Esc", self.icons.quit)).left_aligned())
+            .title_position(ratatui::widgets::block::Position::Top)
+            .title_alignment(ratatui::layout::Alignment::Center);
+
+        if !self.config.layout.sidebar {
+            let position = self.group_position();
+            if position + 1 < self.group_feeds().len() {
+                base_block = base_block
+                    .title_top(Line::from(format!("Next {}", self.icons.next)).right_aligned());
+            }
+            if position > 0 {
+                base_block = base_block
+                    .title_top(Line::from(format!("{} Prev", self.icons.prev)).left_aligned());
+            }
+        }
+
+        let current_feed = &self.feeds[self.selected_feed_idx];
+        match &current_feed.feed {
+            Ok(_) => {
+                let (list_area, preview_area) = self.split_preview(area);
+
+                let mut loaded_rss_block = base_block
+                    .style(self.pane_style(FocusedPane::Entries))
+                    .title(
+                        Line::from(format!(
+                            "{}{}",
+                            current_feed.display_name(),
+                            new_count_label(
+                                current_feed.new_count(),
+                                &format!(" {} ", self.icons.separator)
+                            )
+                        ))
+                        .style(self.theme.text),
+                    );
+                // Shortened to the global keys, then dropped, when it does not fit in the pane
+                let hint = format!(
+                    "Use {} to move, {}",
+                    self.move_hint(false),
+                    keymap::footer(keymap::SELECTION, &self.icons)
+                );
+                if let Some(hint) = [hint, keymap::footer(&[], &self.icons)]
+                    .into_iter()
+                    .find(|hint| hint.width() + 2 <= usize::from(list_area.width))
+                {
+                    loaded_rss_block = loaded_rss_block.title_bottom(hint);
+                }
+
+                let raw_list: Vec<ListItem> = current_feed
+                    .filtered_entries
+                    .iter()
+                    .zip(&current_feed.list_texts)
+                    .map(|(e, text)| self.entry_list_item(e, text, None, current_feed.is_new(e)))
+                    .collect();
+
+                StatefulWidget::render(
+                    entry_list(&self.theme, &self.icons, raw_list, loaded_rss_block),
+                    list_area,
+                    buf,
+                    &mut self.list_state,
+                );
+
+                if let Some(preview_area) = preview_area {
+                    let entry = self.highlighted_entry().cloned();
+                    self.render_preview(entry.as_ref(), preview_area, buf);
+                }
+            }
+            Err(error) => {
+                let failure_block = base_block
+                    .title(Line::from("Error").centered().style(self.theme.text))
+                    .style(self.theme.error);
+
+                Paragraph::new(vec![
+                    Line::from(format!(
+                        "Failed fetching RSS information for {}",
+                        current_feed.url
+                    )),
+                    Line::from(error.clone()),
+                ])
+                .wrap(Wrap { trim: true })
                 .block(failure_block)
                 .render(area, buf);
+            }
         }
     }
 
+    fn show_status(&mut self, text: impl Into<String>) {
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            is_error: false,
+            shown_at: Instant::now(),
+        });
+    }
+
+    fn show_result(&mut self, result: Result<String, String>) {
+        let is_error = result.is_err();
+        self.status_message = Some(StatusMessage {
+            text: result.unwrap_or_else(|err| err),
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+
     pub fn run(mut self, mut terminal: DefaultTerminal) {
         while !self.exit {
+            self.poll_pending_render();
+            if self
+                .status_message
+                .as_ref()
+                .is_some_and(|message| message.shown_at.elapsed() >= Self::STATUS_DURATION)
+            {
+                self.status_message = None;
+            }
             terminal
                 .draw(|frame| frame.render_widget(&mut self, frame.area()))
                 .unwrap();
+            // Only ticks while something changes without input, otherwise waits for the next event
+            let ticking = self.pending_render.is_some() || self.status_message.is_some();
+            if ticking && !event::poll(Self::TICK_RATE).unwrap() {
+                continue;
+            }
             if let Event::Key(key) = event::read().unwrap() {
+                if self.show_help {
+                    self.handle_key_help(key);
+                    continue;
+                }
+                if self.switcher.is_some() {
+                    self.handle_key_switcher(key);
+                    continue;
+                }
+                if self.link_picker.is_some() {
+                    self.handle_key_link_picker(key);
+                    continue;
+                }
+                if key.kind == KeyEventKind::Press && self.handle_key_global(key) {
+                    continue;
+                }
                 match self.screen {
                     CurrentScreen::Selection => self.handle_key_selection(key),
                     CurrentScreen::SingleArticle => self.handle_key_article(key),
+                    CurrentScreen::Timeline => self.handle_key_timeline(key),
                 }
             };
         }
     }
 
-    fn handle_key_article(&mut self, key: KeyEvent) {
+    // Entry under the cursor, or the open article
+    fn highlighted_entry(&self) -> Option<&Entry> {
+        match self.screen {
+            CurrentScreen::Selection => self.feeds[self.selected_feed_idx]
+                .filtered_entries
+                .get(self.list_state.selected()?),
+            CurrentScreen::Timeline => {
+                let &(feed_idx, entry_idx) = self.timeline.get(self.timeline_state.selected()?)?;
+                self.feeds[feed_idx].filtered_entries.get(entry_idx)
+            }
+            CurrentScreen::SingleArticle => self.selected_entry.as_ref(),
+        }
+    }
+
+    // Shared between every screen, returns whether the key was handled
+    fn handle_key_global(&mut self, key: KeyEvent) -> bool {
+        let action = keymap::action_for(keymap::GLOBAL, key);
+        if action == Some(Action::Help) {
+            self.show_help = true;
+            return true;
+        }
+        if action == Some(Action::QuickSwitch) {
+            self.switcher = Some(QuickSwitcher {
+                query: String::new(),
+                list_state: ListState::default().with_selected(Some(0)),
+            });
+            return true;
+        }
+        if !matches!(action, Some(Action::OpenLink | Action::CopyLink)) {
+            return false;
+        }
+
+        let Some(link) = self
+            .highlighted_entry()
+            .and_then(entry_link)
+            .map(ToString::to_string)
+        else {
+            self.show_status("No link available for this entry");
+            return true;
+        };
+
+        let result = if action == Some(Action::OpenLink) {
+            open_link(&self.config.links, &link).map(|()| format!("Opened {link}"))
+        } else {
+            copy_to_clipboard(&self.config.links, &link).map(|()| format!("Copied {link}"))
+        };
+
+        self.show_result(result);
+        true
+    }
+
+    fn handle_key_switcher(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
         }
+        let Some(switcher) = &mut self.switcher else {
+            return;
+        };
 
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.screen = CurrentScreen::Selection;
-                self.selected_entry = None;
-                self.scroll_number.reset();
+        match keymap::action_for(keymap::SWITCHER, key) {
+            Some(Action::Back) => self.switcher = None,
+            Some(Action::Help) => self.show_help = true,
+            Some(Action::Up) => switcher.list_state.select_previous(),
+            Some(Action::Down) => switcher.list_state.select_next(),
+            Some(Action::DeleteCharacter) => {
+                switcher.query.pop();
+                switcher.list_state.select(Some(0));
             }
-            KeyCode::Char('r') => self.scroll_number.reset(),
-            KeyCode::PageUp => {
-                self.scroll_number
-                    .0
-                    .scroll(10, ScrollDirectionVertical::Up)
-                    .ok();
-            }
-            KeyCode::PageDown => {
-                self.scroll_number
-                    .0
-                    .scroll(10, ScrollDirectionVertical::Down)
-                    .ok();
-            }
-            KeyCode::Up => {
-                self.scroll_number
-                    .0
-                    .scroll(1, ScrollDirectionVertical::Up)
-                    .ok();
-            }
-            KeyCode::Down => {
-                self.scroll_number
-                    .0
-                    .scroll(1, ScrollDirectionVertical::Down)
-                    .ok();
-            }
-            KeyCode::Left => {
-                self.scroll_number
-                    .1
-                    .scroll(1, ScrollDirectionHorizontal::Left)
-                    .ok();
-            }
-            KeyCode::Right => {
-                self.scroll_number
-                    .1
-                    .scroll(1, ScrollDirectionHorizontal::Right)
-                    .ok();
+            Some(Action::Open) => {
+                let selected = switcher.list_state.selected().unwrap_or(0);
+                let query = std::mem::take(&mut switcher.query);
+                self.switcher = None;
+                let matches = self.switcher_matches(&query);
+                if let Some(&(feed_idx, _)) = matches.get(selected) {
+                    self.go_to_feed(feed_idx);
+                }
+            }
+            // Everything else is typed into the query
+            None => {
+                if let KeyCode::Char(c) = key.code {
+                    if !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    {
+                        switcher.query.push(c);
+                        switcher.list_state.select(Some(0));
+                    }
+                }
             }
-
             _ => {}
         }
     }
 
-    fn handle_key_selection(&mut self, key: KeyEvent) {
+    // Feeds matching the query, best first, with the positions of the matched characters
+    fn switcher_matches(&self, query: &str) -> Vec<(usize, Vec<usize>)> {
+        let mut matches: Vec<(i64, usize, Vec<usize>)> = self
+            .feeds
+            .iter()
+            .enumerate()
+            .filter_map(|(feed_idx, feed)| {
+                let (score, positions) = fuzzy_match(query, &switcher_label(feed))?;
+                Some((score, feed_idx, positions))
+            })
+            .collect();
+        // Stable, so equally good matches stay in configuration order
+        matches.sort_by_key(|&(score, _, _)| std::cmp::Reverse(score));
+
+        matches
+            .into_iter()
+            .map(|(_, feed_idx, positions)| (feed_idx, positions))
+            .collect()
+    }
+
+    // Shows the feed in the list, switching to its group
+    fn go_to_feed(&mut self, feed_idx: usize) {
+        if self.screen == CurrentScreen::SingleArticle {
+            self.close_article();
+        }
+        self.screen = CurrentScreen::Selection;
+        if let Some(group_idx) = self
+            .groups
+            .iter()
+            .position(|(_, feeds)| feeds.contains(&feed_idx))
+        {
+            self.selected_group_idx = group_idx;
+        }
+        self.selected_feed_idx = feed_idx;
+        self.list_state.select(None);
+    }
+
+    fn handle_key_help(&mut self, key: KeyEvent) {
+        if key.kind == KeyEventKind::Press
+            && keymap::action_for(keymap::HELP, key) == Some(Action::Back)
+        {
+            self.show_help = false;
+        }
+    }
+
+    fn handle_key_link_picker(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
         }
+        let Some(picker_state) = &mut self.link_picker else {
+            return;
+        };
+
+        let Some(action) = keymap::action_for(keymap::LINK_PICKER, key) else {
+            return;
+        };
 
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => self.exit = true,
-            KeyCode::Up => self.list_state.select_previous(),
-            KeyCode::Down => self.list_state.select_next(),
-            KeyCode::Left => {
-                if self.selected_feed_idx.checked_sub(1).is_none() {
+        match action {
+            Action::Back => self.link_picker = None,
+            Action::Help => self.show_help = true,
+            Action::Up => picker_state.select_previous(),
+            Action::Down => picker_state.select_next(),
+            Action::JumpToLink => {
+                let KeyCode::Char(digit @ '1'..='9') = key.code else {
                     return;
+                };
+                let idx = digit as usize - '1' as usize;
+                if idx < self.article_links.len() {
+                    picker_state.select(Some(idx));
                 }
+            }
+            Action::OpenLink | Action::CopyLink => {
+                let Some(link) = picker_state
+                    .selected()
+                    .and_then(|selected| self.article_links.get(selected))
+                else {
+                    return;
+                };
+
+                let result = if action == Action::CopyLink {
+                    copy_to_clipboard(&self.config.links, link).map(|()| format!("Copied {link}"))
+                } else {
+                    open_link(&self.config.links, link).map(|()| format!("Opened {link}"))
+                };
 
-                self.list_state.select(None);
-                self.selected_feed_idx -= 1;
+                self.show_result(result);
+                self.link_picker = None;
             }
-            KeyCode::Right => {
-                if self.selected_feed_idx + 1 >= self.feeds.len() {
+            _ => {}
+        }
+    }
+
+    fn handle_key_article(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(action) = keymap::action_for(keymap::ARTICLE, key) else {
+            return;
+        };
+
+        match action {
+            Action::Back => self.close_article(),
+            Action::NextArticle => self.open_adjacent_entry(true),
+            Action::PreviousArticle => self.open_adjacent_entry(false),
+            Action::ResetPosition => {
+                self.restore_scroll = None;
+                self.scroll_number.reset();
+            }
+            Action::ToggleMetadata => self.show_metadata = !self.show_metadata,
+            Action::Links => {
+                if self.article_links.is_empty() {
+                    self.show_status("No links in this article");
                     return;
                 }
-
-                self.list_state.select(None);
-                self.selected_feed_idx += 1;
+                self.link_picker = Some(ListState::default().with_selected(Some(0)));
             }
-            KeyCode::Char('r') => self.list_state.select(None),
-            KeyCode::Enter => {
-                if self.list_state.selected().is_none() {
+            Action::ToggleSummary => {
+                if !self.selected_entry.as_ref().is_some_and(has_summary_toggle) {
                     return;
                 }
-                self.screen = CurrentScreen::SingleArticle;
-                self.selected_entry = Some(
-                    self.feeds[self.selected_feed_idx]
-                        .clone()
-                        .unwrap()
-                        .filtered_entries[self.list_state.selected().unwrap()]
-                    .clone(),
-                );
+                self.show_summary = !self.show_summary;
+                self.scroll_number.reset();
+                self.render_selected_entry();
+            }
+            Action::PageUp => self.scroll_number.page(ScrollDirection::Backward),
+            Action::PageDown => self.scroll_number.page(ScrollDirection::Forward),
+            Action::Top => self.scroll_number.vertical.reset(),
+            Action::Bottom => self.scroll_number.vertical.scroll_to_end(),
+            Action::ScrollUp => self.scroll_number.vertical.step(ScrollDirection::Backward),
+            Action::ScrollDown => self.scroll_number.vertical.step(ScrollDirection::Forward),
+            Action::ScrollLeft => self
+                .scroll_number
+                .horizontal
+                .step(ScrollDirection::Backward),
+            Action::ScrollRight => self.scroll_number.horizontal.step(ScrollDirection::Forward),
+            _ => {}
+        }
+    }
 
-                // TODO: very ugly and should be fixed
-                let strbuf = self
-                    .selected_entry
-                    .clone()
-                    .unwrap()
-                    .content
-                    .unwrap()
-                    .body
-                    .unwrap();
-
-                let cursor = Cursor::new(strbuf);
-
-                let mut readval =
-                    html2text::from_read(cursor, Self::LARGE_NUMBER).expect("Failed reading HTML");
-
-                if let Some(renderer) = &self.config.renderer {
-                    let mut renderer_command = Command::new(renderer.binary.clone())
-                        .stdin(Stdio::piped())
-                        .stdout(Stdio::piped())
-                        .args(renderer.args.clone().unwrap_or_else(std::vec::Vec::new))
-                        .spawn()
-                        .expect("Failure running renderer command");
-
-                    renderer_command
-                        .stdin
-                        .as_mut()
-                        .unwrap()
-                        .write_all(readval.as_bytes())
-                        .unwrap();
-
-                    readval =
-                        String::from_utf8(renderer_command.wait_with_output().unwrap().stdout)
-                            .unwrap();
+    fn handle_key_selection(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(action) = keymap::action_for(keymap::SELECTION, key) else {
+            return;
+        };
+
+        match action {
+            Action::Quit => self.exit = true,
+            Action::SwitchPane if self.config.layout.sidebar => {
+                self.focus = match self.focus {
+                    FocusedPane::Feeds => FocusedPane::Entries,
+                    FocusedPane::Entries => FocusedPane::Feeds,
+                };
+            }
+            Action::Up if self.sidebar_focused() => self.select_previous_feed(),
+            Action::Down if self.sidebar_focused() => self.select_next_feed(),
+            Action::Open | Action::NextFeed if self.sidebar_focused() => {
+                self.focus = FocusedPane::Entries;
+            }
+            Action::Up => self.list_state.select_previous(),
+            Action::Down => self.list_state.select_next(),
+            Action::PreviousFeed => self.select_previous_feed(),
+            Action::NextFeed => self.select_next_feed(),
+            Action::NextGroup => {
+                self.select_group((self.selected_group_idx + 1) % self.groups.len());
+            }
+            Action::PreviousGroup => self.select_group(
+                self.selected_group_idx
+                    .checked_sub(1)
+                    .unwrap_or(self.groups.len() - 1),
+            ),
+            Action::ResetSelection => self.list_state.select(None),
+            Action::Timeline => self.screen = CurrentScreen::Timeline,
+            Action::JumpToFeed => {
+                let KeyCode::Char(digit @ '1'..='9') = key.code else {
+                    return;
+                };
+                let position = digit as usize - '1' as usize;
+                if let Some(&feed_idx) = self.group_feeds().get(position) {
+                    self.go_to_feed(feed_idx);
                 }
+            }
+            Action::Open => {
+                let Some(selected) = self.list_state.selected().filter(|&selected| {
+                    selected < self.feeds[self.selected_feed_idx].filtered_entries.len()
+                }) else {
+                    return;
+                };
+                self.open_entry(self.selected_feed_idx, selected, CurrentScreen::Selection);
+            }
+            _ => {}
+        }
+    }
+
+    fn sidebar_focused(&self) -> bool {
+        self.config.layout.sidebar && self.focus == FocusedPane::Feeds
+    }
+
+    fn group_feeds(&self) -> &[usize] {
+        &self.groups[self.selected_group_idx].1
+    }
+
+    // Position of the selected feed within its group
+    fn group_position(&self) -> usize {
+        self.group_feeds()
+            .iter()
+            .position(|&feed_idx| feed_idx == self.selected_feed_idx)
+            .unwrap_or(0)
+    }
+
+    fn select_previous_feed(&mut self) {
+        let Some(previous) = self.group_position().checked_sub(1) else {
+            return;
+        };
+
+        self.list_state.select(None);
+        self.selected_feed_idx = self.group_feeds()[previous];
+    }
+
+    fn select_next_feed(&mut self) {
+        let Some(&next) = self.group_feeds().get(self.group_position() + 1) else {
+            return;
+        };
+
+        self.list_state.select(None);
+        self.selected_feed_idx = next;
+    }
+
+    fn select_group(&mut self, group_idx: usize) {
+        self.list_state.select(None);
+        self.selected_group_idx = group_idx;
+        self.selected_feed_idx = self.group_feeds()[0];
+    }
 
-                self.buffered_render = Some(readval);
+    fn handle_key_timeline(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(action) = keymap::action_for(keymap::TIMELINE, key) else {
+            return;
+        };
+
+        match action {
+            Action::Back => self.screen = CurrentScreen::Selection,
+            Action::Up => self.timeline_state.select_previous(),
+            Action::Down => self.timeline_state.select_next(),
+            Action::ResetSelection => self.timeline_state.select(None),
+            Action::Open => {
+                let Some(&(feed_idx, entry_idx)) = self
+                    .timeline_state
+                    .selected()
+                    .and_then(|selected| self.timeline.get(selected))
+                else {
+                    return;
+                };
+                self.open_entry(feed_idx, entry_idx, CurrentScreen::Timeline);
             }
             _ => {}
         }
     }
+
+    fn open_entry(&mut self, feed_idx: usize, entry_idx: usize, origin: CurrentScreen) {
+        let entry = self.feeds[feed_idx].filtered_entries[entry_idx].clone();
+        self.screen = CurrentScreen::SingleArticle;
+        self.article_origin = origin;
+        self.article_feed_idx = feed_idx;
+        self.article_entry_idx = entry_idx;
+        self.scroll_number.reset();
+        self.restore_scroll = self.scroll_positions.get(&entry.id).copied();
+        self.selected_entry = Some(entry);
+        self.show_summary = false;
+        self.render_selected_entry();
+    }
+
+    fn close_article(&mut self) {
+        self.remember_scroll_position();
+        self.selected_entry = None;
+        self.restore_scroll = None;
+        self.scroll_number.reset();
+        self.screen = self.article_origin;
+    }
+
+    fn remember_scroll_position(&mut self) {
+        if let Some(entry) = &self.selected_entry {
+            self.scroll_positions.insert(
+                entry.id.clone(),
+                (
+                    self.scroll_number.vertical.value,
+                    self.scroll_number.horizontal.value,
+                ),
+            );
+        }
+    }
+
+    // Opens the entry before or after the open article, in the list it was opened from
+    fn open_adjacent_entry(&mut self, forward: bool) {
+        let adjacent = match self.article_origin {
+            CurrentScreen::Timeline => {
+                let position = self.timeline_state.selected().and_then(|position| {
+                    if forward {
+                        position.checked_add(1)
+                    } else {
+                        position.checked_sub(1)
+                    }
+                });
+                position
+                    .filter(|&position| position < self.timeline.len())
+                    .map(|position| {
+                        self.timeline_state.select(Some(position));
+                        self.timeline[position]
+                    })
+            }
+            _ => self
+                .adjacent_feed_entry(forward)
+                .inspect(|&(feed_idx, entry_idx)| {
+                    self.selected_feed_idx = feed_idx;
+                    self.list_state.select(Some(entry_idx));
+                }),
+        };
+
+        let Some((feed_idx, entry_idx)) = adjacent else {
+            self.show_status(if forward {
+                "No next article"
+            } else {
+                "No previous article"
+            });
+            return;
+        };
+
+        self.remember_scroll_position();
+        self.open_entry(feed_idx, entry_idx, self.article_origin);
+    }
+
+    // (feed index, entry index) next to the open article, continuing in the feeds of the same group if enabled
+    fn adjacent_feed_entry(&self, forward: bool) -> Option<(usize, usize)> {
+        let entry_count = self.feeds[self.article_feed_idx].filtered_entries.len();
+        let entry_idx = if forward {
+            self.article_entry_idx.checked_add(1)
+        } else {
+            self.article_entry_idx.checked_sub(1)
+        };
+        if let Some(entry_idx) = entry_idx.filter(|&entry_idx| entry_idx < entry_count) {
+            return Some((self.article_feed_idx, entry_idx));
+        }
+
+        if !self.config.navigation.across_feeds {
+            return None;
+        }
+
+        let group_feeds = self.group_feeds();
+        let position = group_feeds
+            .iter()
+            .position(|&feed_idx| feed_idx == self.article_feed_idx)?;
+        let candidates: Vec<usize> = if forward {
+            group_feeds[position + 1..].to_vec()
+        } else {
+            group_feeds[..position].iter().rev().copied().collect()
+        };
+
+        candidates.into_iter().find_map(|feed_idx| {
+            let entry_count = self.feeds[feed_idx].filtered_entries.len();
+            match entry_count {
+                0 => None,
+                _ if forward => Some((feed_idx, 0)),
+                _ => Some((feed_idx, entry_count - 1)),
+            }
+        })
+    }
+
+    fn render_selected_entry(&mut self) {
+        let Some(entry) = &self.selected_entry else {
+            return;
+        };
+        // Not laid out yet, the first draw renders it
+        if self.article_width == 0 {
+            return;
+        }
+
+        let html = if self.show_summary {
+            entry_summary_html(entry)
+        } else {
+            entry_html(entry)
+        };
+
+        self.article_links.clear();
+        self.pending_render = None;
+
+        let Some(strbuf) = html else {
+            self.buffered_render = Some(Text::raw(NO_CONTENT_PLACEHOLDER));
+            return;
+        };
+
+        let Ok(rendered) = render_html(&strbuf, self.article_width.into(), &self.theme) else {
+            self.buffered_render = None;
+            return;
+        };
+        self.article_links.clone_from(&rendered.links);
+
+        let Some(renderer) = self.article_renderer().cloned() else {
+            self.buffered_render = Some(rendered.text);
+            return;
+        };
+        let input = match renderer.input {
+            RendererInput::Text => Ok(rendered.plain()),
+            RendererInput::Html => Ok(strbuf),
+            RendererInput::Markdown => html_to_markdown(&strbuf, Self::LARGE_NUMBER),
+        };
+        let Ok(input) = input else {
+            self.buffered_render = Some(rendered.text);
+            return;
+        };
+
+        self.buffered_render = Some(Text::raw(format!("Rendering with {}...", renderer.binary)));
+        self.pending_render = Some(PendingRender {
+            handle: spawn_renderer(renderer, input, self.article_width),
+            fallback: rendered.text,
+        });
+    }
+
+    // The feed's own renderer wins over the global one, either can be disabled
+    fn article_renderer(&self) -> Option<&RendererConfiguration> {
+        self.feeds[self.article_feed_idx]
+            .renderer
+            .as_ref()
+            .or(self.config.renderer.as_ref())
+            .filter(|renderer| renderer.is_enabled())
+    }
+
+    fn poll_pending_render(&mut self) {
+        let Some(pending) = &self.pending_render else {
+            return;
+        };
+        let Ok(result) = pending.handle.receiver.try_recv() else {
+            return;
+        };
+        let pending = self.pending_render.take().unwrap();
+
+        let output = result.and_then(|stdout| {
+            stdout
+                .into_text()
+                .map_err(|err| format!("Failed reading renderer output: {err}"))
+        });
+
+        self.buffered_render = Some(match output {
+            Ok(text) => text,
+            Err(error) => {
+                let mut text = Text::from(
+                    error
+                        .lines()
+                        .map(|line| Line::from(line.to_string()).style(self.theme.error))
+                        .collect::<Vec<_>>(),
+                );
+                text.push_line(Line::default());
+                text.extend(pending.fallback);
+                text
+            }
+        });
+    }
+}
+
+// Drops the first columns of a line, keeping the styling of what remains
+fn skip_columns(line: &Line<'static>, columns: usize) -> Line<'static> {
+    let mut remaining = columns;
+    let spans: Vec<Span<'static>> = line
+        .spans
+        .iter()
+        .filter_map(|span| {
+            let width = span.content.width();
+            if remaining >= width {
+                remaining -= width;
+                return None;
+            }
+
+            let mut content = String::new();
+            for c in span.content.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if remaining == 0 {
+                    content.push(c);
+                } else if remaining >= char_width {
+                    remaining -= char_width;
+                } else {
+                    // Half of a wide character is left, a space keeps the rest aligned
+                    content.push_str(&" ".repeat(char_width - remaining));
+                    remaining = 0;
+                }
+            }
+            Some(Span::styled(content, span.style))
+        })
+        .collect();
+
+    Line::from(spans).style(line.style)
+}
+
+fn entry_list<'a>(
+    theme: &AppTheme,
+    icons: &'a Icons,
+    items: Vec<ListItem<'a>>,
+    block: Block<'a>,
+) -> List<'a> {
+    List::new(items)
+        .highlight_symbol(&icons.highlight)
+        .style(theme.accent)
+        .highlight_spacing(ratatui::widgets::HighlightSpacing::Always)
+        .highlight_style(theme.text.bold())
+        .direction(ratatui::widgets::ListDirection::TopToBottom)
+        .block(block)
+}
+
+// "<separator>N new", or nothing when there is nothing new
+fn new_count_label(count: usize, separator: &str) -> String {
+    if count == 0 {
+        String::new()
+    } else {
+        format!("{separator}{count} new")
+    }
+}
+
+// Name of the feed, with its own title too when a custom name hides it
+fn switcher_label(feed: &FeedWithCustom) -> String {
+    let title = feed
+        .feed
+        .as_ref()
+        .ok()
+        .and_then(|parsed| parsed.title.as_ref())
+        .map(|title| title.content.clone());
+
+    match (&feed.name, title) {
+        (Some(name), Some(title)) if *name != title => format!("{name} ({title})"),
+        _ => feed.display_name(),
+    }
+}
+
+fn has_summary_toggle(entry: &Entry) -> bool {
+    entry_content_html(entry).is_some() && entry_summary_html(entry).is_some()
+}
+
+fn media_description(url: &str, media_type: Option<String>, size: Option<u64>) -> String {
+    let details: Vec<String> = [media_type, size.map(|size| format!("{size} bytes"))]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if details.is_empty() {
+        url.to_string()
+    } else {
+        format!("{url} ({})", details.join(", "))
+    }
 }